@@ -0,0 +1,73 @@
+//
+//  auth/error.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use jsonwebtoken::errors::Error as JwtError;
+use reqwest::Error as HttpError;
+use reqwest::StatusCode;
+use serde_json::Error as JsonError;
+
+use std::error::Error;
+use std::fmt;
+use std::io::Error as IoError;
+
+/// Errors that arise in the process of authenticating as a GitHub App installation.
+#[derive(Debug)]
+pub enum AuthError {
+    /// The private key file could not be read.
+    IoError(IoError),
+    /// The private key file is not a valid PEM-encoded RSA private key.
+    PemError(JwtError),
+    /// A JSON Web Token could not be created from the private key.
+    JwtError(JwtError),
+    /// The request for an installation access token failed.
+    HttpError(HttpError),
+    /// GitHub responded to the request for an installation access token with an error.
+    StatusError { status: StatusCode, body: String },
+    /// GitHub's response to the request for an installation access token could not be parsed.
+    JsonError(JsonError),
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AuthError::IoError(ref io_err) => write!(f, "IO error reading private key: {}", io_err),
+            AuthError::PemError(ref pem_err) => write!(f, "Invalid private key: {}", pem_err),
+            AuthError::JwtError(ref jwt_err) => write!(f, "Unable to create JWT: {}", jwt_err),
+            AuthError::HttpError(ref http_err) => write!(f, "HTTP error: {}", http_err),
+            AuthError::StatusError { ref status, ref body } => {
+                write!(f, "GitHub returned {} when requesting an installation token: {}", status, body)
+            }
+            AuthError::JsonError(ref json_err) => write!(f, "Invalid installation token response: {}", json_err),
+        }
+    }
+}
+
+impl Error for AuthError {
+    fn description(&self) -> &str {
+        match *self {
+            AuthError::IoError(_) => "IoError",
+            AuthError::PemError(_) => "PemError",
+            AuthError::JwtError(_) => "JwtError",
+            AuthError::HttpError(_) => "HttpError",
+            AuthError::StatusError { .. } => "StatusError",
+            AuthError::JsonError(_) => "JsonError",
+        }
+    }
+}
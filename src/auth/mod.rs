@@ -0,0 +1,188 @@
+//
+//  auth/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `auth` module implements the different ways of authenticating with the GitHub API: either as a user, with a
+//! personal access token, or as a GitHub App installation.
+
+pub mod error;
+
+use self::error::AuthError;
+
+use chrono::{DateTime, Duration, Utc};
+use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+use reqwest::header::ACCEPT;
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use crate::util::rest::Http;
+
+/// The amount of time before an installation access token expires at which it is considered stale and is refreshed.
+const TOKEN_REFRESH_MARGIN_SECS: i64 = 60;
+
+/// The lifetime of the JSON Web Tokens used to request installation access tokens. GitHub allows a maximum of ten
+/// minutes.
+const JWT_LIFETIME_SECS: i64 = 9 * 60;
+
+/// The way in which ghtool authenticates with the GitHub API.
+#[derive(Clone, Debug)]
+pub enum Auth {
+    /// Authenticate as a user with a personal access token.
+    Token(String),
+    /// Authenticate as a GitHub App installation.
+    App(AppAuth),
+}
+
+impl Auth {
+    /// Returns the token to send in the `Authorization` header of requests to the API at `api_url`. For GitHub App
+    /// installations, this will request a new installation access token from that API if there isn't one already, or
+    /// if the existing one is about to expire.
+    pub fn token(&self, http: &Http, api_url: &str) -> Result<String, AuthError> {
+        match *self {
+            Auth::Token(ref token) => Ok(token.clone()),
+            Auth::App(ref app) => app.installation_token(http, api_url),
+        }
+    }
+}
+
+/// The details required to authenticate as a GitHub App installation.
+#[derive(Clone, Debug)]
+pub struct AppAuth {
+    /// The ID of the GitHub App.
+    pub app_id: u64,
+    /// The path to the PEM-encoded private key of the GitHub App.
+    pub private_key_path: PathBuf,
+    /// The ID of the installation of the GitHub App to act as.
+    pub installation_id: u64,
+    /// The most recently obtained installation access token from each API, keyed by the URL of the API. Clones share
+    /// the tokens, so that threads working in parallel don't each request their own.
+    tokens: Arc<Mutex<BTreeMap<String, InstallationToken>>>,
+}
+
+impl AppAuth {
+    pub fn new(app_id: u64, private_key_path: PathBuf, installation_id: u64) -> AppAuth {
+        AppAuth {
+            app_id,
+            private_key_path,
+            installation_id,
+            tokens: Arc::new(Mutex::new(BTreeMap::new())),
+        }
+    }
+
+    /// Returns an installation access token for the API at `api_url`, requesting a new one from it if necessary.
+    pub fn installation_token(&self, http: &Http, api_url: &str) -> Result<String, AuthError> {
+        // Hold the lock while requesting a new token, so that other threads wait for it instead of requesting one too.
+        let mut tokens = self.tokens.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+
+        if let Some(token) = tokens.get(api_url) {
+            if !token.is_stale() {
+                return Ok(token.token.clone());
+            }
+
+            info!("Installation access token expires at {}; refreshing", token.expires_at);
+        }
+
+        let token = self.request_installation_token(http, api_url)?;
+        debug!("Obtained installation access token expiring at {}", token.expires_at);
+
+        let value = token.token.clone();
+        tokens.insert(api_url.to_owned(), token);
+
+        Ok(value)
+    }
+
+    /// Creates a JSON Web Token signed with the app's private key, which identifies ghtool as the GitHub App.
+    fn jwt(&self) -> Result<String, AuthError> {
+        let mut contents = String::new();
+        File::open(&self.private_key_path)
+            .and_then(|mut file| file.read_to_string(&mut contents))
+            .map_err(AuthError::IoError)?;
+
+        let key = EncodingKey::from_rsa_pem(contents.as_bytes()).map_err(AuthError::PemError)?;
+
+        // Backdate the token slightly to allow for clock drift between this machine and GitHub.
+        let now = Utc::now().timestamp();
+        let claims = JwtClaims {
+            iat: now - 60,
+            exp: now + JWT_LIFETIME_SECS,
+            iss: self.app_id,
+        };
+
+        encode(&Header::new(Algorithm::RS256), &claims, &key).map_err(AuthError::JwtError)
+    }
+
+    /// Exchanges a JSON Web Token for an installation access token from the API at `api_url`.
+    fn request_installation_token(&self, http: &Http, api_url: &str) -> Result<InstallationToken, AuthError> {
+        info!("Requesting an installation access token for installation {} from {}...", self.installation_id, api_url);
+
+        let jwt = self.jwt()?;
+
+        let request = http
+            .client()
+            .post(&format!("{}/app/installations/{}/access_tokens", api_url, self.installation_id)[..])
+            .bearer_auth(jwt)
+            .header(ACCEPT, "application/vnd.github.machine-man-preview+json");
+
+        let (status, body) = http
+            .block_on(async move {
+                let response = request.send().await?;
+                let status = response.status();
+                Ok((status, response.bytes().await?))
+            })
+            .map_err(AuthError::HttpError)?;
+
+        if !status.is_success() {
+            return Err(AuthError::StatusError {
+                status,
+                body: String::from_utf8_lossy(&body).into_owned(),
+            });
+        }
+
+        serde_json::from_slice(&body).map_err(AuthError::JsonError)
+    }
+}
+
+/// The claims in the JSON Web Token used to authenticate as a GitHub App.
+#[derive(Serialize)]
+struct JwtClaims {
+    /// The time at which the token was issued.
+    iat: i64,
+    /// The time at which the token expires.
+    exp: i64,
+    /// The ID of the GitHub App.
+    iss: u64,
+}
+
+/// An installation access token, as returned by GitHub.
+#[derive(Clone, Debug, Deserialize)]
+struct InstallationToken {
+    token: String,
+    expires_at: DateTime<Utc>,
+}
+
+impl InstallationToken {
+    /// Returns whether the token has expired, or is about to.
+    fn is_stale(&self) -> bool {
+        self.expires_at - Duration::seconds(TOKEN_REFRESH_MARGIN_SECS) <= Utc::now()
+    }
+}
@@ -0,0 +1,110 @@
+//
+//  cache/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `cache` command, which manages the cache of responses from GitHub.
+
+use clap::ArgMatches;
+
+use std::path::PathBuf;
+
+use crate::client::cache;
+use crate::errors::{ErrorKind, Result};
+use crate::util::output::{self, Format};
+
+/// The result of `cache clear`, as printed in the JSON format.
+#[derive(Serialize)]
+struct ClearOutput {
+    removed: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    dir: Option<PathBuf>,
+}
+
+/// Runs the `cache` command, printing results in `output`.
+pub fn run(output: Format, matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        ("clear", Some(_)) => clear(output),
+        ("", None) => {
+            let _ = details::app().print_help();
+            Err(ErrorKind::NoSubcommand.into())
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// Removes every cached response.
+fn clear(output: Format) -> Result<()> {
+    let removed = cache::clear()?;
+    let dir = cache::dir();
+
+    match output {
+        Format::Text => match dir {
+            Some(ref dir) => println!("Removed {} cached response(s) from {}", removed, dir.display()),
+            None => println!("Removed {} cached response(s)", removed),
+        },
+        Format::Json => output::print_json(&ClearOutput { removed, dir }),
+    }
+
+    Ok(())
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+            .subcommands(subcommands())
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "cache"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Manage the cache of responses from GitHub"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![]
+    }
+
+    /// This command's subcommands.
+    pub fn subcommands() -> Vec<App<'static, 'static>> {
+        vec![App::new("clear").about("Remove every cached response, so that the next requests are sent in full")]
+    }
+}
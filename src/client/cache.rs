@@ -0,0 +1,202 @@
+//
+//  client/cache.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! A cache of responses from the GitHub API, which lets the client send conditional requests.
+//!
+//! Each successful response to a `GET` request that has an `ETag` header is kept in its own file under
+//! `$XDG_CACHE_HOME/ghtool/http`. The next request for the same URL sends the ETag back in an `If-None-Match` header,
+//! and if GitHub responds with 304 Not Modified, which doesn't count towards the rate limit, the cached response is
+//! used instead.
+//!
+//! Responses are keyed by the credentials as well as the URL, since different credentials can see different things.
+//! Like the completion cache, this is only ever a best guess, so failing to read or write it is never treated as an
+//! error.
+
+use reqwest::header::{HeaderValue, ETAG, LINK};
+use reqwest::StatusCode;
+use sha2::{Digest, Sha256};
+
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::auth::Auth;
+use crate::util::dirs;
+use crate::util::rest::Response;
+
+/// Distinguishes the temporary files written by each call to `ResponseCache::put` in this process.
+static NEXT_TEMP: AtomicUsize = AtomicUsize::new(0);
+
+/// A response kept in the cache.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CachedResponse {
+    /// The URL that the response is for, which is only kept to make the cache easier to inspect.
+    pub url: String,
+    pub etag: String,
+    /// The value of the `Link` header, if the response is a page of a list with more pages after it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub link: Option<String>,
+    pub body: String,
+}
+
+impl CachedResponse {
+    /// Turns the cached response back into a response, given the 304 Not Modified response that GitHub sent instead of
+    /// it. The headers of `not_modified`, including the state of the rate limit, are kept.
+    pub fn into_response(self, not_modified: Response) -> Response {
+        let mut headers = not_modified.headers;
+
+        if let Ok(etag) = HeaderValue::from_str(&self.etag[..]) {
+            headers.insert(ETAG, etag);
+        }
+
+        if let Some(link) = self.link.and_then(|link| HeaderValue::from_str(&link[..]).ok()) {
+            headers.insert(LINK, link);
+        }
+
+        Response {
+            status: StatusCode::OK,
+            headers,
+            body: self.body.into_bytes(),
+        }
+    }
+}
+
+/// The responses kept on disk for one set of credentials.
+#[derive(Clone, Debug)]
+pub struct ResponseCache {
+    dir: PathBuf,
+    /// Identifies the credentials that responses are for, without including anything secret itself once hashed.
+    identity: String,
+}
+
+impl ResponseCache {
+    /// Creates a cache of the responses for `auth`, or returns `None` if it isn't possible to determine the user's home
+    /// directory.
+    pub fn new(auth: &Auth) -> Option<ResponseCache> {
+        let identity = match *auth {
+            Auth::Token(ref token) => format!("token {}", token),
+            // Installation access tokens change every hour, but always have the same access.
+            Auth::App(ref app) => format!("app {} installation {}", app.app_id, app.installation_id),
+        };
+
+        dir().map(|dir| ResponseCache { dir, identity })
+    }
+
+    /// Returns the cached response for `url`, if there is one.
+    pub fn get(&self, url: &str) -> Option<CachedResponse> {
+        File::open(self.path(url))
+            .ok()
+            .and_then(|file| serde_json::from_reader::<_, CachedResponse>(file).ok())
+            .filter(|cached| cached.url == url)
+    }
+
+    /// Keeps `response` as the response for `url`, if it has an ETag to send back next time.
+    ///
+    /// The response is written to a temporary file that's then renamed into place, so that another copy of ghtool
+    /// reading the cache at the same time never sees a half-written response.
+    pub fn put(&self, url: &str, response: &Response) {
+        let etag = match response.header("ETag") {
+            Some(etag) => etag.to_owned(),
+            None => return,
+        };
+
+        let body = match String::from_utf8(response.body.clone()) {
+            Ok(body) => body,
+            Err(_) => return,
+        };
+
+        let cached = CachedResponse {
+            url: url.to_owned(),
+            etag,
+            link: response.header("Link").map(str::to_owned),
+            body,
+        };
+
+        let path = self.path(url);
+        let temp_path = path.with_extension(format!(
+            "{}.{}.tmp",
+            process::id(),
+            NEXT_TEMP.fetch_add(1, Ordering::SeqCst)
+        ));
+
+        let result = fs::create_dir_all(&self.dir)
+            .and_then(|_| File::create(&temp_path))
+            .and_then(|file| serde_json::to_writer(file, &cached).map_err(io::Error::other))
+            .and_then(|_| fs::rename(&temp_path, &path));
+
+        if let Err(err) = result {
+            debug!("Unable to write cached response {}: {}", path.display(), err);
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    /// Gets the path to the file that the response for `url` is kept in.
+    fn path(&self, url: &str) -> PathBuf {
+        let digest = Sha256::new()
+            .chain_update(self.identity.as_bytes())
+            .chain_update(b"\n")
+            .chain_update(url.as_bytes())
+            .finalize();
+
+        let name = digest.iter().map(|byte| format!("{:02x}", byte)).collect::<String>();
+        self.dir.join(format!("{}.json", name))
+    }
+}
+
+/// Removes every cached response, returning how many there were.
+pub fn clear() -> io::Result<usize> {
+    let dir = match dir() {
+        Some(dir) => dir,
+        None => return Ok(0),
+    };
+
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(ref err) if err.kind() == io::ErrorKind::NotFound => return Ok(0),
+        Err(err) => return Err(err),
+    };
+
+    // Temporary files left behind by a copy of ghtool that was interrupted are removed too, but aren't counted.
+    let mut removed = 0;
+    for entry in entries {
+        let path = entry?.path();
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("json") => {
+                fs::remove_file(&path)?;
+                removed += 1;
+            }
+            Some("tmp") => fs::remove_file(&path)?,
+            _ => (),
+        }
+    }
+
+    // Only remove the directory itself if nothing else has been put in it.
+    let _ = fs::remove_dir(&dir);
+
+    Ok(removed)
+}
+
+/// Gets the directory that responses are cached in, or `None` if it isn't possible to determine the user's home
+/// directory.
+pub fn dir() -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache_dir| cache_dir.join("http"))
+}
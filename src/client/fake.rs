@@ -0,0 +1,176 @@
+//
+//  client/fake.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! A `Client` that keeps everything in memory instead of talking to GitHub.
+
+use reqwest::StatusCode;
+
+use std::collections::BTreeMap;
+
+use super::{Client, Label, RateLimit};
+use crate::errors::{ErrorKind, Result};
+use crate::util::repo::Repo;
+
+/// A `Client` that keeps repositories and their labels in memory, and responds the way GitHub would.
+#[derive(Debug, Default)]
+pub struct FakeClient {
+    /// The labels in each repository, keyed by "owner/repository". Repositories that aren't here don't exist.
+    pub labels: BTreeMap<String, Vec<Label>>,
+    /// The topics of each repository, keyed by "owner/repository".
+    pub topics: BTreeMap<String, Vec<String>>,
+    /// The repositories that each team has access to, keyed by "org/team".
+    pub teams: BTreeMap<String, Vec<Repo>>,
+    /// The number of requests that can be made before the rate limit is exhausted.
+    pub remaining: Option<u32>,
+}
+
+impl FakeClient {
+    pub fn new() -> FakeClient {
+        FakeClient::default()
+    }
+
+    /// Adds an empty repository.
+    pub fn with_repo(mut self, repo: &Repo) -> FakeClient {
+        self.labels.entry(key(repo)).or_default();
+        self
+    }
+
+    /// Uses up one request, failing if the rate limit has been exhausted.
+    fn request(&mut self) -> Result<()> {
+        match self.remaining {
+            Some(0) => Err(api_error(StatusCode::FORBIDDEN, "API rate limit exceeded")),
+            Some(ref mut remaining) => {
+                *remaining -= 1;
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+
+    /// Returns the repositories whose keys match `filter`.
+    fn repos_where<F: Fn(&str) -> bool>(&self, filter: F) -> Vec<Repo> {
+        self.labels
+            .keys()
+            .filter(|key| filter(&key[..]))
+            .filter_map(|key| Repo::from_string(&key[..]).ok())
+            .collect()
+    }
+}
+
+impl Client for FakeClient {
+    fn each_label(&mut self, repo: &Repo, f: &mut dyn FnMut(Label)) -> Result<()> {
+        self.request()?;
+
+        let labels = self.labels.get(&key(repo)).ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))?;
+        for label in labels {
+            f(label.clone());
+        }
+
+        Ok(())
+    }
+
+    fn find_label(&mut self, repo: &Repo, name: &str) -> Result<Option<Label>> {
+        self.request()?;
+
+        let labels = self.labels.get(&key(repo)).ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))?;
+        Ok(labels.iter().find(|label| label.name.to_lowercase() == name.to_lowercase()).cloned())
+    }
+
+    fn create_label(&mut self, repo: &Repo, label: &Label) -> Result<Label> {
+        self.request()?;
+
+        let labels = self.labels.get_mut(&key(repo)).ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))?;
+        if labels.iter().any(|existing| existing.name.to_lowercase() == label.name.to_lowercase()) {
+            return Err(api_error(StatusCode::UNPROCESSABLE_ENTITY, "Validation Failed"));
+        }
+
+        labels.push(label.clone());
+        Ok(label.clone())
+    }
+
+    fn delete_label(&mut self, repo: &Repo, name: &str) -> Result<()> {
+        self.request()?;
+
+        let labels = self.labels.get_mut(&key(repo)).ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))?;
+        let index = labels
+            .iter()
+            .position(|label| label.name.to_lowercase() == name.to_lowercase())
+            .ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))?;
+
+        labels.remove(index);
+        Ok(())
+    }
+
+    fn owner_repos(&mut self, owner: &str) -> Result<Vec<Repo>> {
+        self.request()?;
+
+        let prefix = format!("{}/", owner);
+        Ok(self.repos_where(|key| key.starts_with(&prefix[..])))
+    }
+
+    fn topic_repos(&mut self, owner: &str, topic: &str) -> Result<Vec<Repo>> {
+        self.request()?;
+
+        let topics = self.topics.clone();
+        let prefix = format!("{}/", owner);
+        Ok(self.repos_where(|key| {
+            key.starts_with(&prefix[..]) && topics.get(key).is_some_and(|topics| topics.iter().any(|t| t == topic))
+        }))
+    }
+
+    fn team_repos(&mut self, org: &str, team: &str) -> Result<Vec<Repo>> {
+        self.request()?;
+
+        self.teams
+            .get(&format!("{}/{}", org, team))
+            .cloned()
+            .ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))
+    }
+
+    fn issue_counts(&mut self, repo: &Repo) -> Result<BTreeMap<String, u64>> {
+        self.request()?;
+
+        // There aren't any issues, so no label is on any of them.
+        self.labels
+            .get(&key(repo))
+            .map(|_| BTreeMap::new())
+            .ok_or(api_error(StatusCode::NOT_FOUND, "Not Found"))
+    }
+
+    fn rate_limit(&self, _host: &str) -> Option<RateLimit> {
+        Some(RateLimit {
+            limit: 5000,
+            remaining: self.remaining.unwrap_or(5000),
+            reset: 0,
+        })
+    }
+}
+
+/// The key that a repository is stored under.
+fn key(repo: &Repo) -> String {
+    format!("{}/{}", repo.user, repo.repo)
+}
+
+fn api_error(status: StatusCode, message: &str) -> crate::errors::Error {
+    ErrorKind::Api {
+        status,
+        message: message.to_owned(),
+    }.into()
+}
@@ -0,0 +1,824 @@
+//
+//  client/github.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use chrono::Utc;
+use reqwest::{Method, StatusCode};
+use serde::de::DeserializeOwned;
+
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::cache::ResponseCache;
+use super::graphql::{self, LabelNode, OwnerReposData, Query, QueryError, RepoNode};
+use super::retry::{self, RetryPolicy};
+use super::{Client, Connector, Label, LabelUsage, RateLimit};
+use crate::auth::Auth;
+use crate::config::Config;
+use crate::errors::{self, ErrorKind, Result};
+use crate::util::error::RestError;
+use crate::util::repo::{self, Repo, DEFAULT_HOST};
+use crate::util::rest::{self, Http, Response};
+
+/// The environment variable that overrides the URL of the API of github.com, e.g. to point ghtool at a fake GitHub
+/// API server in tests.
+pub const API_URL_VAR: &str = "GHTOOL_API_URL";
+
+/// The longest that the client will wait for a rate limit to be lifted before giving up and returning an error.
+const MAX_WAIT_SECS: u64 = 15 * 60;
+
+/// How long to wait after a secondary rate limit if GitHub doesn't say how long to wait in a `Retry-After` header.
+const DEFAULT_RETRY_AFTER_SECS: u64 = 60;
+
+/// Requests are spread out over the time until the rate limit resets once fewer than 1/`PACING_FRACTION` of the
+/// hourly limit are left.
+const PACING_FRACTION: u32 = 10;
+
+/// The longest that the client will wait between requests when spreading them out.
+const MAX_PACING_SECS: u64 = 10;
+
+/// The minimum number of seconds between requests that change something, once GitHub has imposed a secondary rate
+/// limit.
+const WRITE_INTERVAL_SECS: u64 = 1;
+
+/// The number of repositories whose labels are fetched in each GraphQL query. Each one costs GitHub more to answer, so
+/// this keeps queries well within its limits on how much a single query can ask for.
+const GRAPHQL_BATCH_SIZE: usize = 25;
+
+/// A `Client` that talks to GitHub, or a GitHub Enterprise instance, over HTTPS.
+///
+/// The client keeps track of the rate limit from the `X-RateLimit-*` headers of every response, and slows down as it
+/// runs low. If the limit is exhausted, or GitHub imposes a secondary rate limit, the client waits for it to be lifted
+/// if that won't take too long, and otherwise fails with `ErrorKind::RateLimited` without sending anything else.
+///
+/// Requests that fail because GitHub couldn't be reached, didn't respond in time or had a problem of its own are tried
+/// again according to the configured `RetryPolicy`.
+///
+/// Unless the cache is turned off, responses to `GET` requests are kept in a `ResponseCache`, and requests for the same
+/// URL are sent with the cached ETag so that GitHub can respond with 304 Not Modified without using up the rate limit.
+///
+/// The labels of many repositories are fetched at once from the GraphQL API, falling back to the REST API on GitHub
+/// Enterprise instances that don't have one.
+///
+/// Clients for other threads can be created with `connector`. They share the credentials and what's known about the
+/// rate limit, so that together they keep within it.
+pub struct GithubClient {
+    http: Http,
+    auth: Auth,
+    retry: RetryPolicy,
+    cache: Option<ResponseCache>,
+    /// The host that repositories found by searching, e.g. for the members of a selector, belong to.
+    host: String,
+    /// The URL of the API of `host`. Other hosts are GitHub Enterprise instances, whose APIs are always at
+    /// "https://host/api/v3".
+    api_url: String,
+    /// The hosts that credentials may be sent to. Requests for repositories on any other host fail.
+    trusted_hosts: Vec<String>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+/// What every client created from the same `GithubClient` knows about the rate limit.
+#[derive(Debug, Default)]
+struct Shared {
+    /// The last known state of the rate limit on each GitHub instance, keyed by the URL of its API.
+    rate_limits: BTreeMap<String, RateLimit>,
+    /// When the next request that changes something can be sent, once GitHub has imposed a secondary rate limit.
+    next_write: Option<Instant>,
+    /// The URLs of GraphQL APIs that turned out not to exist, so that the REST API is used instead.
+    graphql_unavailable: BTreeSet<String>,
+}
+
+impl GithubClient {
+    /// Creates a client that authenticates with the credentials in `config`.
+    pub fn new(config: &Config) -> Result<GithubClient> {
+        let api_url = match env::var(API_URL_VAR) {
+            Ok(ref url) if !url.is_empty() => {
+                info!("Using the GitHub API at {}, from {}", url, API_URL_VAR);
+                url.trim_end_matches('/').to_owned()
+            }
+            _ => repo::api_url(DEFAULT_HOST),
+        };
+
+        let certificates = match config.ca_bundle() {
+            Some(path) => rest::read_ca_bundle(path)?,
+            None => Vec::new(),
+        };
+
+        Ok(GithubClient {
+            http: Http::new(certificates)?,
+            auth: config.auth().clone(),
+            retry: config.retry(),
+            cache: if config.use_cache() {
+                ResponseCache::new(config.auth())
+            } else {
+                None
+            },
+            host: DEFAULT_HOST.to_owned(),
+            api_url,
+            trusted_hosts: config.trusted_hosts().to_vec(),
+            shared: Arc::new(Mutex::new(Shared::default())),
+        })
+    }
+
+    /// Locks the state shared with the other clients created from the same `GithubClient`.
+    fn shared(&self) -> MutexGuard<'_, Shared> {
+        // Nothing is left half-changed if a thread panics while holding the lock, so it's fine to carry on.
+        self.shared.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Returns the last known state of the rate limit on the API at `api_url`.
+    fn known_rate_limit(&self, api_url: &str) -> Option<RateLimit> {
+        self.shared().rate_limits.get(api_url).cloned()
+    }
+
+    /// Returns the URL of the API of the GitHub instance at `host`, or fails with `ErrorKind::UntrustedHost` if `host`
+    /// isn't trusted with credentials.
+    fn api_url(&self, host: &str) -> Result<String> {
+        if host == self.host {
+            return Ok(self.api_url.clone());
+        }
+
+        if !self.trusted_hosts.iter().any(|trusted| trusted.eq_ignore_ascii_case(host)) {
+            return Err(ErrorKind::UntrustedHost(host.to_owned()).into());
+        }
+
+        Ok(repo::api_url(host))
+    }
+
+    /// Sends a `method` request to `url` on the API at `api_url`, as `send_once` does. If the request is idempotent and
+    /// fails for a reason that might not happen a second time, it's tried again according to the retry policy.
+    ///
+    /// Requests that aren't idempotent are only sent once, since they might have been carried out even though they
+    /// failed. Callers that want to retry them have to check whether that happened first, as `create_label` does.
+    fn send(&mut self, method: Method, api_url: &str, url: &str, body: Option<&[u8]>) -> Result<Response> {
+        if !method.is_idempotent() {
+            return self.send_once(method, api_url, url, body);
+        }
+
+        self.retrying(&format!("{} {}", method, url)[..], |client| {
+            let response = client.send_once(method.clone(), api_url, url, body)?;
+            if retry::is_transient_status(response.status) {
+                // Turn the response into an error so that it's retried.
+                Ok(response.error_for_status()?)
+            } else {
+                Ok(response)
+            }
+        })
+    }
+
+    /// Runs `request`, trying it again according to the retry policy for as long as it fails for a reason that might
+    /// not happen a second time. `description` describes the request in log messages.
+    fn retrying<T, F>(&mut self, description: &str, mut request: F) -> Result<T>
+    where
+        F: FnMut(&mut GithubClient) -> Result<T>,
+    {
+        let mut attempts = 1;
+        loop {
+            match request(self) {
+                Err(ref err) if retry::is_transient(err) && self.retry.allows_retry(attempts) => {
+                    let delay = self.retry.delay(attempts);
+                    warn!(
+                        "{} failed: {}; trying again in {}ms (attempt {} of {})",
+                        description,
+                        err,
+                        delay.as_secs() * 1000 + u64::from(delay.subsec_millis()),
+                        attempts + 1,
+                        self.retry.max_attempts
+                    );
+
+                    thread::sleep(delay);
+                    attempts += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    /// Sends a `method` request to `url` on the API at `api_url`, waiting for the rate limit first if necessary.
+    ///
+    /// Error responses are returned as they are, except for rate limit errors: the request is sent again once the limit
+    /// has been lifted, or if that would take too long, this fails with `ErrorKind::RateLimited`.
+    fn send_once(&mut self, method: Method, api_url: &str, url: &str, body: Option<&[u8]>) -> Result<Response> {
+        let is_write = method != Method::GET;
+
+        // The rate limit for the GraphQL API is separate from the REST API's, so it's kept track of by its URL.
+        let graphql_url = graphql::url(api_url);
+        let rate_limit_url = if url == graphql_url { &graphql_url[..] } else { api_url };
+
+        loop {
+            self.wait_for_rate_limit(rate_limit_url)?;
+
+            if is_write {
+                // Claim the next slot for a write before waiting for it, so that clients on other threads queue up
+                // behind this one rather than all sending their writes at once.
+                let wait = {
+                    let mut shared = self.shared();
+                    let now = Instant::now();
+                    let slot = shared.next_write.map(|next_write| cmp::max(next_write, now));
+                    if let Some(slot) = slot {
+                        shared.next_write = Some(slot + Duration::from_secs(WRITE_INTERVAL_SECS));
+                    }
+
+                    slot.map(|slot| slot - now)
+                };
+
+                if let Some(wait) = wait {
+                    thread::sleep(wait);
+                }
+            }
+
+            let cached = match self.cache {
+                Some(ref cache) if method == Method::GET => cache.get(url),
+                _ => None,
+            };
+            let etag = cached.as_ref().map(|cached| &cached.etag[..]);
+
+            let response = rest::send(&self.http, &self.auth, api_url, method.clone(), url, body, etag)?;
+
+            if let Some(rate_limit) = read_rate_limit(&response) {
+                info!(
+                    "{} of {} requests left until the rate limit resets at {}",
+                    rate_limit.remaining,
+                    rate_limit.limit,
+                    errors::format_time(rate_limit.reset)
+                );
+                self.shared().rate_limits.insert(rate_limit_url.to_owned(), rate_limit);
+            }
+
+            if response.status == StatusCode::NOT_MODIFIED {
+                if let Some(cached) = cached {
+                    debug!("{} hasn't changed; using the cached response", url);
+                    return Ok(cached.into_response(response));
+                }
+            }
+
+            if response.status == StatusCode::OK && method == Method::GET {
+                if let Some(ref cache) = self.cache {
+                    cache.put(url, &response);
+                }
+            }
+
+            if response.status != StatusCode::FORBIDDEN && response.status != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            let message = rest::error_message(&String::from_utf8_lossy(&response.body)).to_lowercase();
+            let retry_after = response.numeric_header("Retry-After");
+
+            if retry_after.is_some() || message.contains("secondary rate limit") || message.contains("abuse") {
+                // Secondary rate limits are imposed for sending too many requests at once, particularly ones that
+                // change something. GitHub didn't carry out the request, so it's safe to send it again.
+                let wait = retry_after.unwrap_or(DEFAULT_RETRY_AFTER_SECS);
+                if wait > MAX_WAIT_SECS {
+                    return Err(ErrorKind::RateLimited(Utc::now().timestamp() as u64 + wait).into());
+                }
+
+                warn!("GitHub imposed a secondary rate limit; waiting {} seconds before trying again", wait);
+                thread::sleep(Duration::from_secs(wait));
+
+                // Slow down from now on, so that it isn't imposed again straight away.
+                let mut shared = self.shared();
+                if shared.next_write.is_none() {
+                    shared.next_write = Some(Instant::now());
+                }
+
+                continue;
+            }
+
+            match self.known_rate_limit(rate_limit_url) {
+                // The rate limit was exhausted, so wait for it to reset before sending the request again.
+                Some(rate_limit) if rate_limit.remaining == 0 => continue,
+                _ => return Ok(response),
+            }
+        }
+    }
+
+    /// Waits until another request can be sent to the API at `api_url` without exceeding the rate limit, or fails with
+    /// `ErrorKind::RateLimited` if that would take longer than `MAX_WAIT_SECS`.
+    fn wait_for_rate_limit(&self, api_url: &str) -> Result<()> {
+        let rate_limit = match self.known_rate_limit(api_url) {
+            Some(rate_limit) => rate_limit,
+            None => return Ok(()),
+        };
+
+        let until_reset = rate_limit.reset.saturating_sub(Utc::now().timestamp() as u64);
+
+        if rate_limit.remaining == 0 {
+            if until_reset > MAX_WAIT_SECS {
+                return Err(ErrorKind::RateLimited(rate_limit.reset).into());
+            }
+
+            warn!(
+                "The rate limit has been exhausted; waiting until it resets at {}",
+                errors::format_time(rate_limit.reset)
+            );
+            // Wait an extra second in case this machine's clock is behind GitHub's.
+            thread::sleep(Duration::from_secs(until_reset + 1));
+        } else if rate_limit.remaining < rate_limit.limit / PACING_FRACTION {
+            // Spread the remaining requests out so that they last until the rate limit resets. If they'd have to be
+            // spread out further than `MAX_PACING_SECS`, there's no point slowing down: the limit will run out either
+            // way, and be waited for then.
+            let interval = until_reset / u64::from(rate_limit.remaining);
+            if interval > 0 && interval <= MAX_PACING_SECS {
+                debug!("Running low on requests; waiting {} seconds", interval);
+                thread::sleep(Duration::from_secs(interval));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Calls `f` with each page of the paginated list at `path` on the API at `api_url`.
+    fn each_page<T, F>(&mut self, api_url: &str, path: &str, mut f: F) -> Result<()>
+    where
+        T: DeserializeOwned,
+        F: FnMut(T),
+    {
+        let mut next = Some(rest::with_page_size(&format!("{}{}", api_url, path)[..]));
+
+        while let Some(url) = next {
+            let response = self.send(Method::GET, api_url, &url[..], None)?.error_for_status()?;
+            next = response.next_page();
+            f(parse(&response)?);
+        }
+
+        Ok(())
+    }
+
+    /// Sends `query` to the GraphQL API of the GitHub instance whose REST API is at `api_url`. Queries only read, so
+    /// unlike other `POST` requests, they're retried in the same way as `GET` requests.
+    ///
+    /// Returns `None` if the instance doesn't have a GraphQL API, in which case the REST API has to be used instead.
+    fn query<T: DeserializeOwned>(&mut self, api_url: &str, query: &Query) -> Result<Option<graphql::Response<T>>> {
+        let url = graphql::url(api_url);
+        if self.shared().graphql_unavailable.contains(&url) {
+            return Ok(None);
+        }
+
+        let body = query.body();
+        loop {
+            let response = self.retrying(&format!("POST {}", url)[..], |client| {
+                let response = client.send_once(Method::POST, api_url, &url[..], Some(&body[..]))?;
+                if retry::is_transient_status(response.status) {
+                    Ok(response.error_for_status()?)
+                } else {
+                    Ok(response)
+                }
+            })?;
+
+            if response.status == StatusCode::NOT_FOUND {
+                info!("There's no GraphQL API at {}; using the REST API instead", url);
+                self.shared().graphql_unavailable.insert(url);
+                return Ok(None);
+            }
+
+            let response = parse::<graphql::Response<T>>(&response.error_for_status()?)?;
+            if !response.errors.iter().any(QueryError::is_rate_limited) {
+                return Ok(Some(response));
+            }
+
+            // GitHub responds to queries that exceed the rate limit with 200 OK, so it has to be noticed here instead
+            // of in `send_once`. If it's known when the limit resets, record that it's been exhausted, so that the
+            // query is sent again once it has, or `send_once` fails with `ErrorKind::RateLimited` if that's too long.
+            let now = Utc::now().timestamp() as u64;
+            match self.shared().rate_limits.get_mut(&url) {
+                Some(rate_limit) if rate_limit.reset > now => rate_limit.remaining = 0,
+                _ => return Ok(Some(response)),
+            }
+        }
+    }
+
+    /// Lists the labels in each of `repos`, which are all on the GitHub instance whose REST API is at `api_url`, from
+    /// its GraphQL API. Returns `None` if there isn't one.
+    fn graphql_labels(
+        &mut self,
+        api_url: &str,
+        repos: &[&Repo],
+        count_issues: bool,
+    ) -> Result<Option<Vec<Result<Vec<LabelUsage>>>>> {
+        let mut labels = repos.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+        let mut errors = repos.iter().map(|_| None).collect::<Vec<Option<errors::Error>>>();
+
+        // The repositories that have more labels to fetch, and the cursor to fetch them after.
+        let mut pending = (0..repos.len()).map(|index| (index, None)).collect::<Vec<(usize, Option<String>)>>();
+
+        while !pending.is_empty() {
+            let query = graphql::labels_query(
+                &pending
+                    .iter()
+                    .map(|&(index, ref after)| (repos[index], after.as_deref()))
+                    .collect::<Vec<_>>()[..],
+                count_issues,
+            );
+
+            let response = match self.query::<BTreeMap<String, Option<RepoNode>>>(api_url, &query)? {
+                Some(response) => response,
+                None => return Ok(None),
+            };
+
+            // Errors with one repository, like it not existing, only affect that repository. Any others mean that the
+            // whole query failed.
+            let mut failed = BTreeMap::new();
+            for error in response.errors {
+                match error.alias().map(str::to_owned) {
+                    Some(alias) => {
+                        failed.insert(alias, error);
+                    }
+                    None => return Err(error.into_error()),
+                }
+            }
+
+            let mut data = response.data.unwrap_or_default();
+            let mut next = Vec::new();
+
+            for (position, (index, _)) in pending.into_iter().enumerate() {
+                let alias = graphql::alias(position);
+
+                match (failed.remove(&alias), data.remove(&alias).and_then(|node| node)) {
+                    (None, Some(node)) => {
+                        if let Some(cursor) = node.labels.next_cursor() {
+                            next.push((index, Some(cursor)));
+                        }
+
+                        labels[index].extend(node.labels.nodes.into_iter().map(LabelNode::into_usage));
+                    }
+                    (Some(error), _) => errors[index] = Some(error.into_error()),
+                    (None, None) => {
+                        errors[index] = Some(
+                            ErrorKind::Api {
+                                status: StatusCode::NOT_FOUND,
+                                message: format!("Could not resolve to a Repository with the name '{}'.", repos[index]),
+                            }.into(),
+                        )
+                    }
+                }
+            }
+
+            pending = next;
+        }
+
+        Ok(Some(
+            labels
+                .into_iter()
+                .zip(errors)
+                .map(|(labels, error)| match error {
+                    Some(error) => Err(error),
+                    None => Ok(labels),
+                })
+                .collect(),
+        ))
+    }
+
+    /// Finds every repository belonging to `owner` with the REST API.
+    fn rest_owner_repos(&mut self, owner: &str) -> Result<Vec<Repo>> {
+        // There's no way to tell whether an owner is a user or an organisation without asking, so try listing an
+        // organisation's repositories first, since that also includes private ones.
+        match self.repos(&format!("/orgs/{}/repos", owner)[..]) {
+            Err(ref err) if err.status().map(|(status, _)| status) == Some(StatusCode::NOT_FOUND) => {
+                self.repos(&format!("/users/{}/repos", owner)[..])
+            }
+            result => result,
+        }
+    }
+
+    /// Fetches every page of a list of repositories from `path` on the API of the client's host.
+    fn repos(&mut self, path: &str) -> Result<Vec<Repo>> {
+        let api_url = self.api_url.clone();
+        let mut api_repos = Vec::new();
+        self.each_page(&api_url[..], path, |page: Vec<ApiRepo>| api_repos.extend(page))?;
+
+        Ok(into_repos(api_repos, &self.host[..]))
+    }
+}
+
+impl Client for GithubClient {
+    fn each_label(&mut self, repo: &Repo, f: &mut dyn FnMut(Label)) -> Result<()> {
+        let api_url = self.api_url(&repo.host[..])?;
+        let path = format!("/repos/{}/{}/labels", repo.user, repo.repo);
+
+        self.each_page(&api_url[..], &path[..], |page: Vec<Label>| {
+            for label in page {
+                f(label);
+            }
+        })
+    }
+
+    fn find_label(&mut self, repo: &Repo, name: &str) -> Result<Option<Label>> {
+        let api_url = self.api_url(&repo.host[..])?;
+        let url = format!(
+            "{}/repos/{}/{}/labels/{}",
+            api_url,
+            repo.user,
+            repo.repo,
+            rest::encode_path_segment(name)
+        );
+
+        let response = self.send(Method::GET, &api_url[..], &url[..], None)?;
+        if response.status == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        parse(&response.error_for_status()?).map(Some)
+    }
+
+    fn create_label(&mut self, repo: &Repo, label: &Label) -> Result<Label> {
+        let api_url = self.api_url(&repo.host[..])?;
+        let url = format!("{}/repos/{}/{}/labels", api_url, repo.user, repo.repo);
+        let body = serde_json::to_vec(label).map_err(RestError::JsonError)?;
+
+        let mut attempted = false;
+        self.retrying(&format!("Creating label {} in {}", label.name, repo)[..], |client| {
+            // GitHub may have created the label even though the request failed, e.g. if the connection was lost before
+            // the response arrived, in which case creating it again would fail because it already exists.
+            if attempted {
+                if let Some(existing) = client.find_label(repo, &label.name[..])? {
+                    info!("Label {} was created in {} by an earlier attempt", existing.name, repo);
+                    return Ok(existing);
+                }
+            }
+
+            attempted = true;
+            let response = client
+                .send(Method::POST, &api_url[..], &url[..], Some(&body[..]))?
+                .error_for_status()?;
+            parse(&response)
+        })
+    }
+
+    fn delete_label(&mut self, repo: &Repo, name: &str) -> Result<()> {
+        let api_url = self.api_url(&repo.host[..])?;
+        let url = format!(
+            "{}/repos/{}/{}/labels/{}",
+            api_url,
+            repo.user,
+            repo.repo,
+            rest::encode_path_segment(name)
+        );
+
+        let mut attempted = false;
+        self.retrying(&format!("Deleting label {} from {}", name, repo)[..], |client| {
+            let response = client.send_once(Method::DELETE, &api_url[..], &url[..], None)?;
+
+            // If an earlier attempt deleted the label but its response was lost, the label is already gone.
+            if attempted && response.status == StatusCode::NOT_FOUND {
+                info!("Label {} was deleted from {} by an earlier attempt", name, repo);
+                return Ok(());
+            }
+
+            attempted = true;
+            response.error_for_status()?;
+            Ok(())
+        })
+    }
+
+    fn owner_repos(&mut self, owner: &str) -> Result<Vec<Repo>> {
+        let api_url = self.api_url.clone();
+        let mut api_repos = Vec::new();
+        let mut after = None;
+
+        loop {
+            let query = graphql::owner_repos_query(owner, after.as_deref());
+            let response = match self.query::<OwnerReposData>(&api_url[..], &query)? {
+                Some(response) => response,
+                None => return self.rest_owner_repos(owner),
+            };
+
+            if let Some(error) = response.errors.into_iter().next() {
+                return Err(error.into_error());
+            }
+
+            let repositories = match response.data.and_then(|data| data.repository_owner) {
+                Some(owner) => owner.repositories,
+                None => {
+                    return Err(ErrorKind::Api {
+                        status: StatusCode::NOT_FOUND,
+                        message: format!("Could not resolve to a RepositoryOwner with the login of '{}'.", owner),
+                    }.into())
+                }
+            };
+
+            after = repositories.next_cursor();
+            api_repos.extend(repositories.nodes.into_iter().map(|node| ApiRepo {
+                name: node.name,
+                owner: ApiOwner {
+                    login: node.owner.login,
+                },
+                archived: node.is_archived,
+            }));
+
+            if after.is_none() {
+                return Ok(into_repos(api_repos, &self.host[..]));
+            }
+        }
+    }
+
+    fn topic_repos(&mut self, owner: &str, topic: &str) -> Result<Vec<Repo>> {
+        let path = format!(
+            "/search/repositories?q=topic:{}+user:{}",
+            rest::encode_path_segment(topic),
+            rest::encode_path_segment(owner)
+        );
+
+        let api_url = self.api_url.clone();
+        let mut api_repos = Vec::new();
+        self.each_page(&api_url[..], &path[..], |page: SearchPage| api_repos.extend(page.items))?;
+
+        Ok(into_repos(api_repos, &self.host[..]))
+    }
+
+    fn team_repos(&mut self, org: &str, team: &str) -> Result<Vec<Repo>> {
+        self.repos(&format!("/orgs/{}/teams/{}/repos", org, team)[..])
+    }
+
+    fn rate_limit(&self, host: &str) -> Option<RateLimit> {
+        self.known_rate_limit(&self.api_url(host).ok()?[..])
+    }
+
+    fn issue_counts(&mut self, repo: &Repo) -> Result<BTreeMap<String, u64>> {
+        let api_url = self.api_url(&repo.host[..])?;
+        let path = format!("/repos/{}/{}/issues?state=all", repo.user, repo.repo);
+
+        let mut counts = BTreeMap::new();
+        self.each_page(&api_url[..], &path[..], |page: Vec<ApiIssue>| {
+            for label in page.into_iter().flat_map(|issue| issue.labels) {
+                *counts.entry(label.name).or_insert(0) += 1;
+            }
+        })?;
+
+        Ok(counts)
+    }
+
+    fn batch_size(&self) -> usize {
+        GRAPHQL_BATCH_SIZE
+    }
+
+    fn bulk_labels(&mut self, repos: &[Repo], count_issues: bool) -> Result<Vec<Result<Vec<LabelUsage>>>> {
+        // The REST API is just as quick for a single repository, and its responses can be cached.
+        if repos.len() < 2 {
+            return Ok(super::labels_one_by_one(self, repos, count_issues));
+        }
+
+        let mut results = repos.iter().map(|_| None).collect::<Vec<_>>();
+        let hosts = repos.iter().map(|repo| &repo.host[..]).collect::<BTreeSet<_>>();
+
+        // Each GitHub instance has its own GraphQL API, so each one's repositories are fetched separately.
+        for host in hosts {
+            let indices = (0..repos.len()).filter(|&index| repos[index].host == host).collect::<Vec<_>>();
+            let api_url = match self.api_url(host) {
+                Ok(api_url) => api_url,
+                Err(_) => {
+                    for index in indices {
+                        results[index] = Some(Err(ErrorKind::UntrustedHost(host.to_owned()).into()));
+                    }
+                    continue;
+                }
+            };
+
+            let host_repos = indices.iter().map(|&index| &repos[index]).collect::<Vec<_>>();
+            let host_results = match self.graphql_labels(&api_url[..], &host_repos[..], count_issues)? {
+                Some(host_results) => host_results,
+                None => {
+                    let host_repos = host_repos.into_iter().cloned().collect::<Vec<_>>();
+                    super::labels_one_by_one(self, &host_repos[..], count_issues)
+                }
+            };
+
+            for (index, result) in indices.into_iter().zip(host_results) {
+                results[index] = Some(result);
+            }
+        }
+
+        // Listing repositories one by one stops once the rate limit is exhausted, so there may not be a result for
+        // every one.
+        Ok(results.into_iter().map_while(|result| result).collect())
+    }
+
+    fn connector(&self) -> Option<Box<dyn Connector>> {
+        Some(Box::new(GithubConnector {
+            http: self.http.clone(),
+            auth: self.auth.clone(),
+            retry: self.retry,
+            cache: self.cache.clone(),
+            host: self.host.clone(),
+            api_url: self.api_url.clone(),
+            trusted_hosts: self.trusted_hosts.clone(),
+            shared: self.shared.clone(),
+        }))
+    }
+}
+
+/// Creates `GithubClient`s for other threads, sharing the connections, credentials and rate limit of the one it was
+/// created from.
+struct GithubConnector {
+    http: Http,
+    auth: Auth,
+    retry: RetryPolicy,
+    cache: Option<ResponseCache>,
+    host: String,
+    api_url: String,
+    trusted_hosts: Vec<String>,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl Connector for GithubConnector {
+    fn connect(&self) -> Result<Box<dyn Client>> {
+        Ok(Box::new(GithubClient {
+            http: self.http.clone(),
+            auth: self.auth.clone(),
+            retry: self.retry,
+            cache: self.cache.clone(),
+            host: self.host.clone(),
+            api_url: self.api_url.clone(),
+            trusted_hosts: self.trusted_hosts.clone(),
+            shared: self.shared.clone(),
+        }))
+    }
+}
+
+/// Parses the body of `response` as JSON.
+fn parse<T: DeserializeOwned>(response: &Response) -> Result<T> {
+    Ok(serde_json::from_slice(&response.body[..]).map_err(RestError::JsonError)?)
+}
+
+/// Reads the state of the rate limit from the `X-RateLimit-*` headers of `response`, if it has them. GitHub Enterprise
+/// instances with rate limiting turned off don't send them.
+fn read_rate_limit(response: &Response) -> Option<RateLimit> {
+    Some(RateLimit {
+        limit: response.numeric_header("X-RateLimit-Limit")? as u32,
+        remaining: response.numeric_header("X-RateLimit-Remaining")? as u32,
+        reset: response.numeric_header("X-RateLimit-Reset")?,
+    })
+}
+
+/// A page of results from the `/search/repositories` endpoint.
+#[derive(Deserialize)]
+struct SearchPage {
+    items: Vec<ApiRepo>,
+}
+
+/// An issue or pull request, as returned by GitHub when listing issues.
+#[derive(Deserialize)]
+struct ApiIssue {
+    labels: Vec<ApiIssueLabel>,
+}
+
+/// A label on an issue, of which only the name is needed.
+#[derive(Deserialize)]
+struct ApiIssueLabel {
+    name: String,
+}
+
+/// A repository, as returned by GitHub when listing or searching for repositories.
+#[derive(Deserialize)]
+struct ApiRepo {
+    name: String,
+    owner: ApiOwner,
+    #[serde(default)]
+    archived: bool,
+}
+
+/// The owner of a repository, as returned by GitHub.
+#[derive(Deserialize)]
+struct ApiOwner {
+    login: String,
+}
+
+/// Converts repositories returned by the GitHub instance at `host` into `Repo`s, leaving out archived repositories
+/// since they're read-only.
+fn into_repos(api_repos: Vec<ApiRepo>, host: &str) -> Vec<Repo> {
+    api_repos
+        .into_iter()
+        .filter(|api_repo| {
+            if api_repo.archived {
+                info!("Skipping archived repository {}/{}", api_repo.owner.login, api_repo.name);
+            }
+
+            !api_repo.archived
+        })
+        .map(|api_repo| Repo {
+            host: host.to_owned(),
+            user: api_repo.owner.login,
+            repo: api_repo.name,
+        })
+        .collect()
+}
@@ -0,0 +1,277 @@
+//
+//  client/graphql.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The queries that `GithubClient` sends to the GitHub GraphQL API, and the responses it gets back.
+//!
+//! One GraphQL query can fetch the labels of many repositories at once, by giving each repository its own alias ("r0",
+//! "r1" and so on) with its own variables. Lists are paginated with cursors: each connection says whether it has more
+//! items, and the cursor to pass as `after` to fetch them, so a repository with more labels than fit in one page is
+//! asked for again in the next query, until none of them have any more.
+
+use chrono::Utc;
+use reqwest::StatusCode;
+use serde_json::{json, Map, Value};
+
+use std::fmt::Write;
+
+use super::{Label, LabelUsage};
+use crate::errors::{Error, ErrorKind};
+use crate::util::repo::Repo;
+
+/// The number of items to request in each page of a connection, which is the most that GitHub allows.
+const PAGE_SIZE: usize = 100;
+
+/// The longest that GitHub's GraphQL rate limit can take to reset, in seconds.
+const RATE_LIMIT_WINDOW_SECS: u64 = 60 * 60;
+
+/// A query, along with the values of its variables.
+#[derive(Debug)]
+pub struct Query {
+    pub text: String,
+    pub variables: Map<String, Value>,
+}
+
+impl Query {
+    /// The body of the request to send the query in.
+    pub fn body(&self) -> Vec<u8> {
+        let body = json!({ "query": self.text, "variables": self.variables });
+        serde_json::to_vec(&body).unwrap_or_default()
+    }
+}
+
+/// Returns the URL of the GraphQL API of the GitHub instance whose REST API is at `api_url`. For github.com, this is
+/// "https://api.github.com/graphql", and for GitHub Enterprise instances, "https://host/api/graphql".
+pub fn url(api_url: &str) -> String {
+    match api_url.strip_suffix("/api/v3") {
+        Some(base) => format!("{}/api/graphql", base),
+        None => format!("{}/graphql", api_url),
+    }
+}
+
+/// The alias that the repository at `index` in a labels query is given.
+pub fn alias(index: usize) -> String {
+    format!("r{}", index)
+}
+
+/// Builds a query for the next page of labels in each of `repos`, starting after the cursor given with it, or from
+/// the beginning if there isn't one. With `count_issues`, the number of issues and pull requests with each label is
+/// fetched as well.
+pub fn labels_query(repos: &[(&Repo, Option<&str>)], count_issues: bool) -> Query {
+    let mut parameters = Vec::new();
+    let mut fields = String::new();
+    let mut variables = Map::new();
+
+    let label_fields = if count_issues {
+        "name color description issues { totalCount } pullRequests { totalCount }"
+    } else {
+        "name color description"
+    };
+
+    for (index, &(repo, after)) in repos.iter().enumerate() {
+        parameters.push(format!("$owner{0}: String!, $name{0}: String!, $after{0}: String", index));
+        let _ = write!(
+            fields,
+            " {alias}: repository(owner: $owner{index}, name: $name{index}) {{ labels(first: {size}, after: \
+             $after{index}) {{ pageInfo {{ hasNextPage endCursor }} nodes {{ {fields} }} }} }}",
+            alias = alias(index),
+            index = index,
+            size = PAGE_SIZE,
+            fields = label_fields
+        );
+
+        variables.insert(format!("owner{}", index), Value::from(&repo.user[..]));
+        variables.insert(format!("name{}", index), Value::from(&repo.repo[..]));
+        variables.insert(format!("after{}", index), after.map_or(Value::Null, Value::from));
+    }
+
+    Query {
+        text: format!("query Labels({}) {{{} }}", parameters.join(", "), fields),
+        variables,
+    }
+}
+
+/// Builds a query for the next page of repositories belonging to the user or organisation `login`, starting after
+/// `after`, or from the beginning if it's `None`.
+pub fn owner_repos_query(login: &str, after: Option<&str>) -> Query {
+    let text = format!(
+        "query OwnerRepos($login: String!, $after: String) {{ repositoryOwner(login: $login) {{ repositories(first: \
+         {}, after: $after, ownerAffiliations: [OWNER]) {{ pageInfo {{ hasNextPage endCursor }} nodes {{ name owner \
+         {{ login }} isArchived }} }} }} }}",
+        PAGE_SIZE
+    );
+
+    let mut variables = Map::new();
+    variables.insert("login".to_owned(), Value::from(login));
+    variables.insert("after".to_owned(), after.map_or(Value::Null, Value::from));
+
+    Query { text, variables }
+}
+
+/// The response to a query. GitHub responds with 200 OK even if parts of the query failed, in which case `data` holds
+/// whatever could be fetched, and `errors` says what couldn't.
+#[derive(Debug, Deserialize)]
+pub struct Response<T> {
+    pub data: Option<T>,
+    #[serde(default)]
+    pub errors: Vec<QueryError>,
+}
+
+/// Something that went wrong with part of a query.
+#[derive(Debug, Deserialize)]
+pub struct QueryError {
+    /// The kind of error, e.g. "NOT_FOUND".
+    #[serde(rename = "type", default)]
+    pub kind: Option<String>,
+    pub message: String,
+    /// The path to the field that failed, starting with its alias.
+    #[serde(default)]
+    pub path: Vec<Value>,
+}
+
+impl QueryError {
+    /// The alias of the top-level field that failed, if the error was with a single field.
+    pub fn alias(&self) -> Option<&str> {
+        self.path.first().and_then(Value::as_str)
+    }
+
+    /// Whether the query failed because the GraphQL rate limit has been exhausted. GitHub still responds with 200 OK
+    /// when it has.
+    pub fn is_rate_limited(&self) -> bool {
+        self.kind.as_ref().is_some_and(|kind| kind == "RATE_LIMITED")
+    }
+
+    /// Converts this into the same error that the REST API would have caused, so that it's reported, and ghtool exits,
+    /// in the same way whichever API was used.
+    ///
+    /// `GithubClient` waits for the rate limit itself when it knows when the limit resets, so a rate limit error only
+    /// gets this far when it doesn't, in which case the latest the limit can reset is assumed.
+    pub fn into_error(self) -> Error {
+        let status = match self.kind.as_ref().map(|kind| &kind[..]) {
+            Some("NOT_FOUND") => StatusCode::NOT_FOUND,
+            Some("FORBIDDEN") => StatusCode::FORBIDDEN,
+            Some("RATE_LIMITED") => {
+                return ErrorKind::RateLimited(Utc::now().timestamp() as u64 + RATE_LIMIT_WINDOW_SECS).into()
+            }
+            _ => StatusCode::BAD_REQUEST,
+        };
+
+        ErrorKind::Api {
+            status,
+            message: self.message,
+        }.into()
+    }
+}
+
+/// A paginated list of items.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Connection<T> {
+    pub page_info: PageInfo,
+    pub nodes: Vec<T>,
+}
+
+impl<T> Connection<T> {
+    /// The cursor to fetch the next page after, or `None` if this is the last page.
+    pub fn next_cursor(&self) -> Option<String> {
+        if self.page_info.has_next_page {
+            self.page_info.end_cursor.clone()
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PageInfo {
+    pub has_next_page: bool,
+    pub end_cursor: Option<String>,
+}
+
+/// A repository, as returned by a labels query.
+#[derive(Debug, Deserialize)]
+pub struct RepoNode {
+    pub labels: Connection<LabelNode>,
+}
+
+/// A label, as returned by a labels query.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelNode {
+    name: String,
+    color: String,
+    description: Option<String>,
+    issues: Option<Count>,
+    pull_requests: Option<Count>,
+}
+
+impl LabelNode {
+    pub fn into_usage(self) -> LabelUsage {
+        let issues = match (self.issues, self.pull_requests) {
+            (None, None) => None,
+            (issues, pull_requests) => Some(
+                issues.map_or(0, |count| count.total_count) + pull_requests.map_or(0, |count| count.total_count),
+            ),
+        };
+
+        LabelUsage {
+            label: Label {
+                name: self.name,
+                color: self.color,
+                // The REST API leaves out empty descriptions, so do the same here.
+                description: self.description.filter(|description| !description.is_empty()),
+            },
+            issues,
+        }
+    }
+}
+
+/// The number of items in a connection, without the items themselves.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Count {
+    total_count: u64,
+}
+
+/// The response to an owner's repositories query.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerReposData {
+    pub repository_owner: Option<OwnerNode>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OwnerNode {
+    pub repositories: Connection<OwnerRepoNode>,
+}
+
+/// A repository, as returned by an owner's repositories query.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OwnerRepoNode {
+    pub name: String,
+    pub owner: Login,
+    pub is_archived: bool,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Login {
+    pub login: String,
+}
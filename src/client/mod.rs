@@ -0,0 +1,175 @@
+//
+//  client/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The layer that every command talks to GitHub through.
+//!
+//! Commands are written against the `Client` trait rather than against the REST API directly, so that everything to do
+//! with actually making requests (the runtime, credentials, which URL to send requests to, retrying requests that fail
+//! and keeping track of the rate limit) lives in one place, and so that a fake implementation can be swapped in.
+
+pub mod cache;
+pub mod github;
+pub mod graphql;
+pub mod retry;
+
+#[cfg(test)]
+pub mod fake;
+
+pub use self::github::GithubClient;
+
+use std::collections::BTreeMap;
+
+use crate::errors::Result;
+use crate::util::exit::ExitCode;
+use crate::util::repo::Repo;
+
+/// A label in a repository.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    pub name: String,
+    /// The label's colour, as six hexadecimal digits, e.g. "ee0701".
+    pub color: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// A label, along with the number of issues and pull requests in its repository that have it, if that was asked for.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LabelUsage {
+    pub label: Label,
+    pub issues: Option<u64>,
+}
+
+/// The state of the GitHub API rate limit for the current credentials.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+pub struct RateLimit {
+    /// The number of requests that can be made each hour.
+    pub limit: u32,
+    /// The number of requests that can be made before the limit is exhausted.
+    pub remaining: u32,
+    /// When the limit resets, in seconds since the Unix epoch.
+    pub reset: u64,
+}
+
+/// A connection to GitHub.
+pub trait Client {
+    /// Calls `f` with each of the labels in `repo`, as soon as it's found.
+    fn each_label(&mut self, repo: &Repo, f: &mut dyn FnMut(Label)) -> Result<()>;
+
+    /// Finds the label named `name` in `repo`, returning `None` if there isn't one. Like GitHub, the name is compared
+    /// case-insensitively.
+    fn find_label(&mut self, repo: &Repo, name: &str) -> Result<Option<Label>>;
+
+    /// Creates `label` in `repo`, returning the label as GitHub created it.
+    fn create_label(&mut self, repo: &Repo, label: &Label) -> Result<Label>;
+
+    /// Deletes the label named `name` from `repo`, which also removes it from every issue and pull request that has it.
+    fn delete_label(&mut self, repo: &Repo, name: &str) -> Result<()>;
+
+    /// Finds every repository belonging to the user or organisation `owner`, leaving out archived repositories.
+    fn owner_repos(&mut self, owner: &str) -> Result<Vec<Repo>>;
+
+    /// Finds every repository belonging to the user or organisation `owner` with `topic`, leaving out archived
+    /// repositories.
+    fn topic_repos(&mut self, owner: &str, topic: &str) -> Result<Vec<Repo>>;
+
+    /// Finds every repository that the team `team` in the organisation `org` has access to, leaving out archived
+    /// repositories.
+    fn team_repos(&mut self, org: &str, team: &str) -> Result<Vec<Repo>>;
+
+    /// Returns the state of the rate limit on the GitHub instance at `host` as of the last response from it, without
+    /// sending a request, or `None` if it isn't known.
+    fn rate_limit(&self, host: &str) -> Option<RateLimit>;
+
+    /// Counts the issues and pull requests in `repo` that have each label, keyed by the name of the label. Labels that
+    /// aren't on any issues or pull requests are left out.
+    fn issue_counts(&mut self, repo: &Repo) -> Result<BTreeMap<String, u64>>;
+
+    /// The number of repositories that `bulk_labels` is best given at once.
+    fn batch_size(&self) -> usize {
+        1
+    }
+
+    /// Lists the labels in each of `repos`, returning the result for each repository in the same order. With
+    /// `count_issues`, the number of issues and pull requests with each label is counted as well.
+    ///
+    /// By default, each repository is listed in turn with `labels` and `issue_counts`, stopping if the rate limit is
+    /// exhausted, so the results may be cut short. Clients that can fetch the labels of many repositories at once
+    /// override this, and `batch_size`.
+    fn bulk_labels(&mut self, repos: &[Repo], count_issues: bool) -> Result<Vec<Result<Vec<LabelUsage>>>> {
+        Ok(labels_one_by_one(self, repos, count_issues))
+    }
+
+    /// Returns a `Connector` that creates clients for other threads to use, or `None` if this client can't be used from
+    /// more than one thread, in which case everything has to be done on the current thread.
+    fn connector(&self) -> Option<Box<dyn Connector>> {
+        None
+    }
+
+    /// Lists the labels in `repo`.
+    fn labels(&mut self, repo: &Repo) -> Result<Vec<Label>> {
+        let mut labels = Vec::new();
+        self.each_label(repo, &mut |label| labels.push(label))?;
+        Ok(labels)
+    }
+}
+
+/// Lists the labels in each of `repos` in turn with `client`, as `Client::bulk_labels` does by default.
+pub fn labels_one_by_one<C: Client + ?Sized>(
+    client: &mut C,
+    repos: &[Repo],
+    count_issues: bool,
+) -> Vec<Result<Vec<LabelUsage>>> {
+    let mut results = Vec::new();
+
+    for repo in repos {
+        let result = client.labels(repo).and_then(|labels| {
+            let counts = if count_issues {
+                Some(client.issue_counts(repo)?)
+            } else {
+                None
+            };
+
+            Ok(labels
+                .into_iter()
+                .map(|label| LabelUsage {
+                    issues: counts.as_ref().map(|counts| counts.get(&label.name).cloned().unwrap_or(0)),
+                    label,
+                })
+                .collect())
+        });
+
+        // Once the rate limit has been exhausted, every other request would fail too.
+        let exhausted = result.as_ref().err().is_some_and(|err| err.exit_code() == ExitCode::RateLimited);
+        results.push(result);
+        if exhausted {
+            break;
+        }
+    }
+
+    results
+}
+
+/// Creates clients for other threads, which share whatever the client it came from knows about GitHub, like the state
+/// of the rate limit.
+pub trait Connector: Send + Sync {
+    /// Creates a client for use on the current thread.
+    fn connect(&self) -> Result<Box<dyn Client>>;
+}
@@ -0,0 +1,159 @@
+//
+//  client/retry.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! How requests that fail for reasons that might not happen a second time are retried.
+
+use reqwest::StatusCode;
+use rand::{self, Rng};
+
+use std::cmp;
+use std::time::Duration;
+
+use crate::errors::{Error, ErrorKind};
+
+/// How many times to try a request, and how long to wait in between.
+///
+/// The delay doubles after each attempt, starting at `initial_delay_ms` and going up to at most `max_delay_ms`. With
+/// `jitter`, each delay is a random amount between half and all of that, so that many copies of ghtool that failed at
+/// the same time don't all try again at the same time too.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The total number of times to try a request, including the first. This is always at least 1.
+    pub max_attempts: u32,
+    pub initial_delay_ms: u64,
+    pub max_delay_ms: u64,
+    pub jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> RetryPolicy {
+        RetryPolicy {
+            max_attempts: 3,
+            initial_delay_ms: 500,
+            max_delay_ms: 30_000,
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns whether a request that has been tried `attempts` times may be tried again.
+    pub fn allows_retry(&self, attempts: u32) -> bool {
+        attempts < cmp::max(self.max_attempts, 1)
+    }
+
+    /// Returns how long to wait before trying a request again, after it's been tried `attempts` times.
+    pub fn delay(&self, attempts: u32) -> Duration {
+        let exponent = cmp::min(attempts.saturating_sub(1), 16);
+        let delay_ms = cmp::min(self.initial_delay_ms.saturating_mul(1 << exponent), self.max_delay_ms);
+
+        if self.jitter && delay_ms > 1 {
+            Duration::from_millis(delay_ms / 2 + rand::thread_rng().gen_range(0, delay_ms / 2 + 1))
+        } else {
+            Duration::from_millis(delay_ms)
+        }
+    }
+}
+
+/// Returns whether a response with `status` means that GitHub had a problem that might not happen a second time.
+pub fn is_transient_status(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::INTERNAL_SERVER_ERROR
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Returns whether `err` might not happen if the request that caused it was tried again: GitHub couldn't be reached,
+/// the connection was lost or timed out, or GitHub had a problem of its own.
+pub fn is_transient(err: &Error) -> bool {
+    match *err.kind() {
+        ErrorKind::Request => true,
+        ErrorKind::Api { status, .. } => is_transient_status(status),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::util::error::RestError;
+
+    #[test]
+    fn delay_doubles_up_to_the_maximum() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            initial_delay_ms: 100,
+            max_delay_ms: 1000,
+            jitter: false,
+        };
+
+        let delays = (1..7).map(|attempts| policy.delay(attempts)).collect::<Vec<_>>();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 800, 1000, 1000]
+                .iter()
+                .map(|&ms| Duration::from_millis(ms))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn jitter_keeps_delay_between_half_and_all_of_it() {
+        let policy = RetryPolicy {
+            jitter: true,
+            ..RetryPolicy::default()
+        };
+
+        for _ in 0..100 {
+            let delay = policy.delay(2);
+            assert!(delay >= Duration::from_millis(500) && delay <= Duration::from_millis(1000));
+        }
+    }
+
+    #[test]
+    fn always_allows_at_least_one_attempt() {
+        let policy = RetryPolicy {
+            max_attempts: 0,
+            ..RetryPolicy::default()
+        };
+
+        assert!(!policy.allows_retry(1));
+        assert!(RetryPolicy::default().allows_retry(2));
+        assert!(!RetryPolicy::default().allows_retry(3));
+    }
+
+    #[test]
+    fn only_retries_errors_that_might_not_happen_again() {
+        let json_err = serde_json::from_str::<u64>("{").unwrap_err();
+        let status_err = |status| RestError::StatusError {
+            status,
+            body: String::new(),
+        };
+
+        assert!(is_transient(&Error::from(RestError::Timeout)));
+        assert!(is_transient(&Error::from(status_err(StatusCode::BAD_GATEWAY))));
+        assert!(!is_transient(&Error::from(status_err(StatusCode::NOT_FOUND))));
+        assert!(!is_transient(&Error::from(RestError::JsonError(json_err))));
+    }
+}
@@ -0,0 +1,106 @@
+//
+//  completions/cache.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! A cache of the names of the repositories and labels that ghtool has seen, which is used to suggest completions.
+//!
+//! The cache is only ever a best guess, so failing to read or write it is never treated as an error.
+
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, File};
+use std::io;
+use std::path::PathBuf;
+use std::process;
+
+use crate::util::dirs;
+use crate::util::repo::Repo;
+
+/// The names of the repositories and labels that ghtool has seen.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Cache {
+    /// Every repository that ghtool has acted on.
+    #[serde(default)]
+    pub repos: BTreeSet<String>,
+    /// The names of the labels in each repository, keyed by the repository.
+    #[serde(default)]
+    pub labels: BTreeMap<String, BTreeSet<String>>,
+}
+
+impl Cache {
+    /// Loads the cache from disk, or returns an empty cache if it doesn't exist or can't be read.
+    pub fn load() -> Cache {
+        path()
+            .and_then(|path| File::open(path).ok())
+            .and_then(|file| serde_json::from_reader(file).ok())
+            .unwrap_or_default()
+    }
+
+    /// Writes the cache to disk. It's written to a temporary file that's then renamed into place, so that a shell
+    /// reading it to complete a command never sees it half-written.
+    pub fn save(&self) {
+        let path = match path() {
+            Some(path) => path,
+            None => return,
+        };
+        let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+
+        let result = path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| File::create(&temp_path))
+            .and_then(|file| serde_json::to_writer(file, self).map_err(io::Error::other))
+            .and_then(|_| fs::rename(&temp_path, &path));
+
+        if let Err(err) = result {
+            debug!("Unable to write completion cache {}: {}", path.display(), err);
+            let _ = fs::remove_file(&temp_path);
+        }
+    }
+
+    /// Records that `repos` exist.
+    pub fn add_repos<'r, I: IntoIterator<Item = &'r Repo>>(&mut self, repos: I) {
+        self.repos.extend(repos.into_iter().map(Repo::to_string));
+    }
+
+    /// Records that `repo` contains exactly the labels named `names`.
+    pub fn set_labels<I: IntoIterator<Item = String>>(&mut self, repo: &Repo, names: I) {
+        self.repos.insert(repo.to_string());
+        self.labels.insert(repo.to_string(), names.into_iter().collect());
+    }
+
+    /// Records that `repo` contains the labels named `names`, in addition to any others it's already known to contain.
+    pub fn add_labels<I: IntoIterator<Item = String>>(&mut self, repo: &Repo, names: I) {
+        self.repos.insert(repo.to_string());
+        self.labels
+            .entry(repo.to_string())
+            .or_default()
+            .extend(names);
+    }
+}
+
+/// Loads the cache, passes it to `update`, and then saves it again.
+pub fn update<F: FnOnce(&mut Cache)>(update: F) {
+    let mut cache = Cache::load();
+    update(&mut cache);
+    cache.save();
+}
+
+/// Gets the path to the cache file, or `None` if it isn't possible to determine the user's home directory.
+fn path() -> Option<PathBuf> {
+    dirs::cache_dir().map(|cache_dir| cache_dir.join("completions.json"))
+}
@@ -0,0 +1,133 @@
+//
+//  completions/complete.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The hidden `complete-args` command, which the completion scripts run to find suggestions for arguments that clap
+//! doesn't know the possible values of.
+
+use clap::ArgMatches;
+
+use crate::completions::cache::Cache;
+use crate::config::{Locations, StoredConfig};
+use crate::util::repo::Repo;
+
+/// Prints each suggestion for the kind of argument given in `matches` that starts with the given prefix, one per line.
+pub fn run(locations: &Locations, matches: &ArgMatches) {
+    let prefix = matches.value_of("prefix").unwrap_or("");
+    let cache = Cache::load();
+
+    let candidates: Vec<String> = match matches.value_of("kind") {
+        Some("repo") => {
+            // Saved groups can be used anywhere a repository can, so suggest those too.
+            let groups = StoredConfig::try_load(locations)
+                .ok()
+                .and_then(|stored| stored.groups)
+                .unwrap_or_default();
+
+            cache
+                .repos
+                .into_iter()
+                .chain(groups.keys().map(|name| format!(":{}", name)))
+                .collect()
+        }
+        Some("label") => match matches.value_of("repo") {
+            Some(repo_string) => {
+                // The cache is keyed by the canonical form of each repository, which may differ from what was typed.
+                let key = Repo::from_string(repo_string)
+                    .map(|repo| repo.to_string())
+                    .unwrap_or(repo_string.to_owned());
+
+                cache
+                    .labels
+                    .get(&key)
+                    .map(|labels| labels.iter().cloned().collect())
+                    .unwrap_or_default()
+            }
+            None => {
+                let mut labels = cache.labels.values().flat_map(|labels| labels.iter().cloned()).collect::<Vec<_>>();
+                labels.sort();
+                labels.dedup();
+                labels
+            }
+        },
+        _ => Vec::new(),
+    };
+
+    for candidate in candidates.iter().filter(|candidate| candidate.starts_with(prefix)) {
+        println!("{}", candidate);
+    }
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, AppSettings, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .setting(AppSettings::Hidden)
+            .args(&args()[..])
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "complete-args"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Print suggestions for completing an argument, for use by completion scripts"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("kind")
+                .index(1)
+                .value_name("KIND")
+                .help("The kind of argument to complete")
+                .possible_values(&["repo", "label"])
+                .takes_value(true)
+                .required(true),
+            Arg::with_name("prefix")
+                .index(2)
+                .value_name("PREFIX")
+                .help("The part of the argument that has already been typed")
+                .takes_value(true),
+            Arg::with_name("repo")
+                .long("repo")
+                .value_name("REPO")
+                .help("Only suggest the labels in this repository")
+                .takes_value(true),
+        ]
+    }
+}
@@ -0,0 +1,218 @@
+//
+//  completions/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `completions` command, which generates shell completion scripts.
+//!
+//! The scripts that clap generates only know about the arguments defined in each command's `details` module. To also
+//! complete the names of repositories and labels, they're wrapped so that they ask the hidden `complete-args` command
+//! for suggestions wherever a repository or label is expected.
+
+pub mod cache;
+pub mod complete;
+
+use clap::{App, ArgMatches, Shell};
+
+use std::str;
+
+use crate::errors::Result;
+use crate::util::error::ArgError;
+
+/// The name of the binary that the generated scripts complete.
+const BIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// Completes repository and label names in bash, falling back to the generated completions for everything else.
+const BASH_WRAPPER: &str = r#"_ghtool() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local prev="${COMP_WORDS[COMP_CWORD-1]}"
+
+    if [[ "${cur}" != -* ]]; then
+        if [[ "${prev}" == -l || "${prev}" == --label ]]; then
+            # Suggest the labels in the repository being copied from, if it's been given.
+            local from="" i
+            for ((i = 1; i < COMP_CWORD - 1; i++)); do
+                if [[ "${COMP_WORDS[i]}" == -f || "${COMP_WORDS[i]}" == --from ]]; then
+                    from="${COMP_WORDS[i+1]}"
+                fi
+            done
+
+            local labels="$(ghtool complete-args label ${from:+--repo "${from}"} "${cur}" 2>/dev/null)"
+
+            # Label names can contain spaces, so only split suggestions on newlines.
+            local IFS=$'\n'
+            COMPREPLY=($(compgen -W "${labels}" -- "${cur}"))
+            return 0
+        fi
+
+        if [[ "${prev}" == -f || "${prev}" == --from || "${prev}" == -t || "${prev}" == --to ]] ||
+            [[ " ${COMP_WORDS[*]:0:COMP_CWORD} " == *" label "*" list "* ]]; then
+            COMPREPLY=($(compgen -W "$(ghtool complete-args repo "${cur}" 2>/dev/null)" -- "${cur}"))
+            return 0
+        fi
+    fi
+
+    _ghtool_static "$@"
+}
+
+"#;
+
+/// Completes repository and label names in zsh, falling back to the generated completions for everything else.
+const ZSH_WRAPPER: &str = r#"_ghtool() {
+    if [[ "${PREFIX}" != -* ]]; then
+        if [[ "${words[CURRENT-1]}" == (-l|--label) ]]; then
+            # Suggest the labels in the repository being copied from, if it's been given.
+            local -a labels repo
+            local from=${words[(I)(-f|--from)]}
+            (( from > 0 && from < CURRENT - 1 )) && repo=(--repo "${words[from+1]}")
+            labels=(${(f)"$(ghtool complete-args label "${repo[@]}" "${PREFIX}" 2>/dev/null)"})
+            compadd -a labels
+            return
+        fi
+
+        if [[ "${words[CURRENT-1]}" == (-f|--from|-t|--to) ]] ||
+            [[ " ${words[1,CURRENT-1]} " == *" label "*" list "* ]]; then
+            local -a repos
+            repos=(${(f)"$(ghtool complete-args repo "${PREFIX}" 2>/dev/null)"})
+            compadd -a repos
+            return
+        fi
+    fi
+
+    _ghtool_static "$@"
+}
+
+"#;
+
+/// Completes repository and label names in fish, in addition to the generated completions.
+const FISH_COMPLETIONS: &str = r#"
+complete -c ghtool -n "__fish_seen_subcommand_from list" -f -a "(ghtool complete-args repo (commandline -ct))"
+complete -c ghtool -n "__fish_seen_subcommand_from copy" -s f -l from -x \
+    -a "(ghtool complete-args repo (commandline -ct))"
+complete -c ghtool -n "__fish_seen_subcommand_from copy" -s t -l to -x \
+    -a "(ghtool complete-args repo (commandline -ct))"
+complete -c ghtool -n "__fish_seen_subcommand_from copy" -s l -l label -x \
+    -a "(ghtool complete-args label (commandline -ct))"
+"#;
+
+/// Prints the completion script for the shell given in `matches`. `app` is the definition of the whole app, which the
+/// script is generated from.
+pub fn run(app: App<'static, 'static>, matches: &ArgMatches) -> Result<()> {
+    let shell_string = matches
+        .value_of("shell")
+        .ok_or(ArgError::NoValue { arg: "shell" })?;
+
+    let shell = shell_string
+        .parse::<Shell>()
+        .map_err(|_| ArgError::InvalidValue {
+            arg: "shell",
+            value: shell_string.to_owned(),
+        })?;
+
+    print!("{}", script(app, shell));
+
+    Ok(())
+}
+
+/// Generates the completion script for `shell` from `app`.
+fn script(mut app: App<'static, 'static>, shell: Shell) -> String {
+    let mut generated = Vec::new();
+    app.gen_completions_to(BIN_NAME, shell, &mut generated);
+    let generated = String::from_utf8_lossy(&generated);
+
+    // The generated bash and zsh scripts each define a single function that does all the completion, so rename it and
+    // put a wrapper in its place that handles repositories and labels before deferring to it.
+    let function = format!("_{}() {{", BIN_NAME);
+    let renamed = format!("_{}_static() {{", BIN_NAME);
+
+    match shell {
+        Shell::Bash => generated.replacen(&function[..], &format!("{}{}", BASH_WRAPPER, renamed)[..], 1),
+        Shell::Zsh => generated.replacen(&function[..], &format!("{}{}", ZSH_WRAPPER, renamed)[..], 1),
+        Shell::Fish => format!("{}{}", generated, FISH_COMPLETIONS),
+        _ => generated.into_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_a_script_for_every_shell() {
+        for shell in &["bash", "zsh", "fish"] {
+            let script = script(crate::details::app(), shell.parse().unwrap());
+            assert!(script.contains("complete-args label"), "no label completion for {}", shell);
+        }
+    }
+
+    #[test]
+    fn wraps_the_generated_bash_and_zsh_functions() {
+        for shell in &[Shell::Bash, Shell::Zsh] {
+            let script = script(crate::details::app(), *shell);
+            assert!(script.contains("_ghtool_static \"$@\""));
+            assert!(script.contains("_ghtool_static() {"));
+        }
+    }
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "completions"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Print a shell completion script"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("shell")
+                .index(1)
+                .value_name("SHELL")
+                .help("The shell to print the completion script for")
+                .possible_values(&["bash", "zsh", "fish"])
+                .takes_value(true)
+                .required(true),
+        ]
+    }
+}
@@ -0,0 +1,708 @@
+//
+//  config/command/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `config` command, which inspects and edits the user's configuration file.
+
+use clap::ArgMatches;
+use toml::value::{Table, Value};
+
+use std::env;
+use std::io;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use crate::config::{self, ConfigError, IntoConfig, Locations, StoredConfig, ValueType};
+use crate::errors::{Error, ErrorKind, Result};
+use crate::util::error::ArgError;
+use crate::util::output::{self, Format};
+use crate::util::rest;
+
+/// Runs the `config` command. `token` is the value of the top-level `--token` argument, if any, which `config init`
+/// writes to the new configuration file, and `output` is the format to print results in.
+pub fn run(locations: &Locations, token: Option<&str>, output: Format, matches: &ArgMatches) -> Result<()> {
+    match matches.subcommand() {
+        ("init", Some(init_matches)) => init(locations, token, output, init_matches),
+        ("get", Some(get_matches)) => get(locations, output, get_matches),
+        ("set", Some(set_matches)) => set(locations, output, set_matches),
+        ("unset", Some(unset_matches)) => unset(locations, output, unset_matches),
+        ("path", Some(path_matches)) => path(locations, output, path_matches),
+        ("validate", Some(_)) => validate(locations, output),
+        ("", None) => {
+            let _ = details::app().print_help();
+            Err(ErrorKind::NoSubcommand.into())
+        }
+        _ => unreachable!(),
+    }
+}
+
+/// The result of a `config` subcommand, as printed in the JSON format.
+#[derive(Default, Serialize)]
+struct ConfigOutput<'v> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    key: Option<&'v str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    value: Option<&'v Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'v Path>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    paths: Option<Vec<&'v Path>>,
+}
+
+/// The result of `config validate`, as printed in the JSON format.
+#[derive(Serialize)]
+struct ValidateOutput<'p> {
+    valid: bool,
+    /// The configuration files that were checked.
+    files: Vec<&'p Path>,
+    problems: &'p [Problem],
+}
+
+/// A problem found by `config validate`.
+#[derive(Serialize)]
+struct Problem {
+    /// The file the problem is in, if it's in a particular file.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+    /// The one-based line and column the problem is at, if it's known.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    column: Option<usize>,
+    message: String,
+}
+
+/// Creates a new user configuration file containing a personal access token.
+fn init(
+    locations: &Locations,
+    token: Option<&str>,
+    output: Format,
+    matches: &ArgMatches,
+) -> Result<()> {
+    let path = user_path(locations)?;
+    if path.exists() && !matches.is_present("force") {
+        return Err(ErrorKind::AlreadyExists(path).into());
+    }
+
+    let access_token = match token {
+        Some(token) => token.to_owned(),
+        None => prompt("GitHub personal access token: ")?,
+    };
+
+    let stored = StoredConfig {
+        access_token: Some(access_token),
+        ..StoredConfig::default()
+    };
+
+    let path = stored.save(locations)?;
+
+    match output {
+        Format::Text => println!("Created configuration file at {}", path.display()),
+        Format::Json => output::print_json(&ConfigOutput {
+            path: Some(&path),
+            ..ConfigOutput::default()
+        }),
+    }
+
+    Ok(())
+}
+
+/// Prints the value of a key, taking into account every configuration file that applies.
+fn get(locations: &Locations, output: Format, matches: &ArgMatches) -> Result<()> {
+    let key = key_argument(matches)?;
+    let table = config::merged_table(locations)?;
+
+    match (output, lookup(&table, key)) {
+        (_, None) => return Err(ErrorKind::KeyNotSet(key.to_owned()).into()),
+        (Format::Text, Some(Value::String(string))) => println!("{}", string),
+        (Format::Text, Some(value)) => println!("{}", value),
+        (Format::Json, Some(value)) => output::print_json(&ConfigOutput {
+            key: Some(key),
+            value: Some(value),
+            ..ConfigOutput::default()
+        }),
+    }
+
+    Ok(())
+}
+
+/// Sets the value of a key in the user configuration file, creating the file if necessary.
+fn set(locations: &Locations, output: Format, matches: &ArgMatches) -> Result<()> {
+    let key = key_argument(matches)?;
+    let raw_value = matches
+        .value_of("value")
+        .ok_or(ArgError::NoValue { arg: "value" })?;
+
+    let value_type = config::find_key(key)
+        .map(|key| key.value_type)
+        .ok_or(ErrorKind::UnknownKey(key.to_owned()))?;
+
+    let value = match value_type {
+        ValueType::String => Value::String(raw_value.to_owned()),
+        // Relative paths in the configuration file are relative to the file itself, so make paths given on the command
+        // line absolute to keep them relative to the working directory.
+        ValueType::Path => {
+            let path = env::current_dir().map(|dir| dir.join(raw_value))?;
+            Value::String(path.to_string_lossy().into_owned())
+        }
+        ValueType::StringArray => Value::Array(
+            raw_value
+                .split(',')
+                .map(|item| Value::String(item.trim().to_owned()))
+                .collect(),
+        ),
+        ValueType::Integer => raw_value
+            .parse::<i64>()
+            .map(Value::Integer)
+            .map_err(|_| ErrorKind::InvalidValue {
+                key: key.to_owned(),
+                value: raw_value.to_owned(),
+                expected: value_type,
+            })?,
+        ValueType::Boolean => raw_value
+            .parse::<bool>()
+            .map(Value::Boolean)
+            .map_err(|_| ErrorKind::InvalidValue {
+                key: key.to_owned(),
+                value: raw_value.to_owned(),
+                expected: value_type,
+            })?,
+    };
+
+    let path = user_path(locations)?;
+    let mut table = if path.exists() {
+        config::read_table(&path)?
+    } else {
+        Table::new()
+    };
+
+    insert(&mut table, key, value.clone());
+    save_table(&path, table)?;
+
+    if output.is_json() {
+        output::print_json(&ConfigOutput {
+            key: Some(key),
+            value: Some(&value),
+            path: Some(&path),
+            ..ConfigOutput::default()
+        });
+    }
+
+    Ok(())
+}
+
+/// Removes a key from the user configuration file.
+fn unset(locations: &Locations, output: Format, matches: &ArgMatches) -> Result<()> {
+    let key = key_argument(matches)?;
+    let path = user_path(locations)?;
+    let mut table = config::read_table(&path)?;
+
+    let segments = key.split('.').collect::<Vec<_>>();
+    if !remove(&mut table, &segments[..]) {
+        return Err(ErrorKind::KeyNotSet(key.to_owned()).into());
+    }
+
+    save_table(&path, table)?;
+
+    if output.is_json() {
+        output::print_json(&ConfigOutput {
+            key: Some(key),
+            path: Some(&path),
+            ..ConfigOutput::default()
+        });
+    }
+
+    Ok(())
+}
+
+/// Prints the path to the user configuration file or, with `--all`, the path to every configuration file that exists.
+fn path(locations: &Locations, output: Format, matches: &ArgMatches) -> Result<()> {
+    if matches.is_present("all") {
+        let paths = locations.existing();
+
+        match output {
+            Format::Text => for path in paths {
+                println!("{}", path.display());
+            },
+            Format::Json => output::print_json(&ConfigOutput {
+                paths: Some(paths),
+                ..ConfigOutput::default()
+            }),
+        }
+    } else {
+        let path = user_path(locations)?;
+
+        match output {
+            Format::Text => println!("{}", path.display()),
+            Format::Json => output::print_json(&ConfigOutput {
+                path: Some(&path),
+                ..ConfigOutput::default()
+            }),
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks every configuration file that exists for syntax errors, unknown keys and values of the wrong type, printing
+/// the location of each problem found.
+fn validate(locations: &Locations, output: Format) -> Result<()> {
+    let paths = locations.existing();
+    if paths.is_empty() {
+        return Err(ConfigError::FileMissing.into());
+    }
+
+    let mut problems = Vec::new();
+    for path in &paths {
+        let project = locations.project.as_ref().is_some_and(|project| project == path);
+        let file_problems = validate_file(path, project)?;
+
+        if file_problems.is_empty() && !output.is_json() {
+            println!("{}: OK", path.display());
+        }
+
+        problems.extend(file_problems);
+    }
+
+    // Credentials and the CA bundle can come from any of the files, so only check them once all of the files are known
+    // to be valid.
+    if problems.is_empty() {
+        let checked = StoredConfig::try_load(locations).and_then(|stored| {
+            if let Some(ref path) = stored.ca_bundle {
+                rest::read_ca_bundle(path)?;
+            }
+
+            IntoConfig::from_stored(stored, None)
+        });
+        if let Err(err) = checked {
+            problems.push(Problem {
+                path: None,
+                line: None,
+                column: None,
+                message: err.to_string(),
+            });
+        }
+    }
+
+    match output {
+        Format::Text => for problem in &problems {
+            match (&problem.path, problem.line, problem.column) {
+                (Some(path), Some(line), Some(column)) => {
+                    println!("{}:{}:{}: {}", path.display(), line, column, problem.message)
+                }
+                (Some(path), _, _) => println!("{}: {}", path.display(), problem.message),
+                (&None, _, _) => println!("{}", problem.message),
+            }
+        },
+        Format::Json => output::print_json(&ValidateOutput {
+            valid: problems.is_empty(),
+            files: paths,
+            problems: &problems[..],
+        }),
+    }
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(ErrorKind::InvalidConfig(problems.len()).into())
+    }
+}
+
+/// Checks a single configuration file, which is the project-local one if `project` is true, returning each problem
+/// found.
+fn validate_file(path: &Path, project: bool) -> Result<Vec<Problem>> {
+    let contents = config::read_contents(path)?;
+
+    // Each problem is a message and, if it's known, the zero-based line and column it applies to.
+    let mut problems: Vec<(Option<(usize, usize)>, String)> = Vec::new();
+
+    match toml::from_str::<Table>(&contents[..]) {
+        Err(err) => problems.push((err.line_col(), err.to_string())),
+        Ok(table) => {
+            for key in unknown_keys(&table, "") {
+                problems.push((locate_key(&contents[..], &key[..]), format!("unknown key \"{}\"", key)));
+            }
+
+            if project {
+                for key in table.keys().filter(|key| !config::is_project_key(key)) {
+                    problems.push((
+                        locate_key(&contents[..], &key[..]),
+                        format!("\"{}\" can't be set in a project-local configuration file", key),
+                    ));
+                }
+            }
+
+            if let Err(err) = toml::from_str::<StoredConfig>(&contents[..]) {
+                problems.push((err.line_col(), err.to_string()));
+            }
+        }
+    }
+
+    Ok(problems
+        .into_iter()
+        .map(|(position, message)| Problem {
+            path: Some(path.to_path_buf()),
+            line: position.map(|(line, _)| line + 1),
+            column: position.map(|(_, col)| col + 1),
+            message,
+        })
+        .collect())
+}
+
+/// Gets the path to the user configuration file, which is the one that `config` edits.
+fn user_path(locations: &Locations) -> Result<PathBuf> {
+    locations
+        .user
+        .clone()
+        .ok_or(ConfigError::FileMissing.into())
+}
+
+/// Gets the value of the "key" argument, and checks that it's a valid configuration key.
+fn key_argument<'a>(matches: &'a ArgMatches) -> Result<&'a str> {
+    let key = matches
+        .value_of("key")
+        .ok_or(ArgError::NoValue { arg: "key" })?;
+
+    match config::find_key(key) {
+        Some(_) => Ok(key),
+        None => Err(ErrorKind::UnknownKey(key.to_owned()).into()),
+    }
+}
+
+/// Writes `table` to the configuration file at `path`.
+///
+/// Only the key being changed is checked, by `set`, against the type in `KEYS`. The rest of the table isn't, so that
+/// keys that only make sense together, like those in `[app]`, can be set one at a time, and so that a problem elsewhere
+/// in the file doesn't stop it from being fixed. `ghtool config validate` checks the whole file.
+fn save_table(path: &Path, table: Table) -> Result<()> {
+    let contents = toml::to_string(&Value::Table(table))
+        .map_err(ConfigError::SerializeError)?;
+
+    config::write_contents(path, &contents[..]).map_err(Error::from)
+}
+
+/// Looks up the value of the dot-separated `key` in `table`.
+fn lookup<'t>(table: &'t Table, key: &str) -> Option<&'t Value> {
+    let mut segments = key.split('.');
+    let first = segments.next().and_then(|segment| table.get(segment));
+
+    segments.fold(first, |value, segment| value.and_then(|value| value.get(segment)))
+}
+
+/// Sets the value of the dot-separated `key` in `table` to `value`, creating any intermediate tables required.
+fn insert(table: &mut Table, key: &str, value: Value) {
+    let mut segments = key.split('.').collect::<Vec<_>>();
+    let last = segments.pop().unwrap_or(key);
+
+    let mut current = table;
+    for segment in segments {
+        let entry = current
+            .entry(segment.to_owned())
+            .or_insert_with(|| Value::Table(Table::new()));
+
+        if !entry.is_table() {
+            *entry = Value::Table(Table::new());
+        }
+
+        current = match *entry {
+            Value::Table(ref mut child) => child,
+            _ => unreachable!(),
+        };
+    }
+
+    current.insert(last.to_owned(), value);
+}
+
+/// Removes the key made up of `segments` from `table`, along with any tables that are left empty as a result. Returns
+/// whether the key was present.
+fn remove(table: &mut Table, segments: &[&str]) -> bool {
+    if segments.len() == 1 {
+        return table.remove(segments[0]).is_some();
+    }
+
+    let (removed, now_empty) = match table.get_mut(segments[0]) {
+        Some(&mut Value::Table(ref mut child)) => {
+            let removed = remove(child, &segments[1..]);
+            (removed, child.is_empty())
+        }
+        _ => (false, false),
+    };
+
+    if now_empty {
+        table.remove(segments[0]);
+    }
+
+    removed
+}
+
+/// Returns the dot-separated names of all the keys and tables in `table` that aren't valid configuration keys.
+fn unknown_keys(table: &Table, prefix: &str) -> Vec<String> {
+    let mut unknown = Vec::new();
+
+    for (name, value) in table {
+        let key = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+
+        match *value {
+            Value::Table(ref child) if config::is_table(&key[..]) => unknown.extend(unknown_keys(child, &key[..])),
+            _ if config::find_key(&key[..]).is_some() => (),
+            _ => unknown.push(key),
+        }
+    }
+
+    unknown
+}
+
+/// Finds the zero-based line and column at which `key` is defined in `contents`. This only understands the simple
+/// `[table]` and `key = value` layout that `ghtool config` itself writes, so it may return `None` for keys defined in
+/// other ways.
+fn locate_key(contents: &str, key: &str) -> Option<(usize, usize)> {
+    let mut table = String::new();
+
+    for (line_number, line) in contents.lines().enumerate() {
+        let trimmed = line.trim_start();
+        let column = line.len() - trimmed.len();
+
+        if trimmed.starts_with('#') {
+            continue;
+        } else if trimmed.starts_with('[') {
+            let name = trimmed.trim_start_matches('[').split(']').next().unwrap_or("").trim();
+            if name == key {
+                return Some((line_number, column));
+            }
+
+            table = name.to_owned();
+        } else if let Some(equals) = trimmed.find('=') {
+            let name = trimmed[..equals].trim().trim_matches('"');
+            let full_name = if table.is_empty() {
+                name.to_owned()
+            } else {
+                format!("{}.{}", table, name)
+            };
+
+            if full_name == key {
+                return Some((line_number, column));
+            }
+        }
+    }
+
+    None
+}
+
+/// Prints `message` and reads a line from standard input.
+fn prompt(message: &str) -> io::Result<String> {
+    // The prompt goes to stderr so that stdout only ever contains results.
+    eprint!("{}", message);
+    io::stderr().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    Ok(input.trim().to_owned())
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+            .subcommands(subcommands())
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "config"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Inspect and edit ghtool's configuration file"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![]
+    }
+
+    /// This command's subcommands.
+    pub fn subcommands() -> Vec<App<'static, 'static>> {
+        vec![
+            App::new("init")
+                .about(
+                    "Create a configuration file containing the access token given by --token, or prompt for one if \
+                     --token isn't given",
+                )
+                .arg(
+                    Arg::with_name("force")
+                        .short("f")
+                        .long("force")
+                        .help("Overwrite the configuration file if it already exists"),
+                ),
+            App::new("get")
+                .about("Print the value of a configuration key")
+                .arg(key_arg()),
+            App::new("set")
+                .about("Set the value of a configuration key")
+                .arg(key_arg())
+                .arg(
+                    Arg::with_name("value")
+                        .index(2)
+                        .value_name("VALUE")
+                        .help("The new value of the key")
+                        .takes_value(true)
+                        .required(true),
+                ),
+            App::new("unset")
+                .about("Remove a configuration key")
+                .arg(key_arg()),
+            App::new("path")
+                .about("Print the path to the user configuration file, which is the one that `set` and `unset` edit")
+                .arg(
+                    Arg::with_name("all")
+                        .short("a")
+                        .long("all")
+                        .help("Print the path to every configuration file that applies, in increasing order of precedence"),
+                ),
+            App::new("validate").about("Check every configuration file that applies for errors and unknown keys"),
+        ]
+    }
+
+    /// The argument naming a configuration key, shared by several subcommands.
+    fn key_arg() -> Arg<'static, 'static> {
+        Arg::with_name("key")
+            .index(1)
+            .value_name("KEY")
+            .help("The configuration key, e.g. \"access_token\" or \"app.app_id\"")
+            .takes_value(true)
+            .required(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::fs;
+
+    const CONTENTS: &str = "access_token = \"secret\"\n\n\
+                            [app]\napp_id = 1\nprivate_key_path = \"key.pem\"\ninstallation_id = 2\n\n\
+                            [groups]\nweb = [\"acme/web\"]\n";
+
+    fn table() -> Table {
+        toml::from_str(CONTENTS).unwrap()
+    }
+
+    #[test]
+    fn gets_values_by_dotted_key() {
+        let table = table();
+
+        assert_eq!(lookup(&table, "access_token"), Some(&Value::String("secret".to_owned())));
+        assert_eq!(lookup(&table, "app.app_id"), Some(&Value::Integer(1)));
+        assert_eq!(lookup(&table, "app.installation_id"), Some(&Value::Integer(2)));
+        assert_eq!(lookup(&table, "defaults.repo"), None);
+    }
+
+    #[test]
+    fn sets_values_creating_tables() {
+        let mut table = table();
+
+        insert(&mut table, "defaults.repo", Value::String("acme/web".to_owned()));
+        insert(&mut table, "app.app_id", Value::Integer(2));
+
+        assert_eq!(lookup(&table, "defaults.repo"), Some(&Value::String("acme/web".to_owned())));
+        assert_eq!(lookup(&table, "app.app_id"), Some(&Value::Integer(2)));
+    }
+
+    #[test]
+    fn unsets_values_removing_empty_tables() {
+        let mut table = table();
+
+        assert!(remove(&mut table, &["app", "app_id"]));
+        assert!(table.contains_key("app"));
+        assert!(!remove(&mut table, &["app", "app_id"]));
+        assert!(remove(&mut table, &["groups", "web"]));
+        assert!(!table.contains_key("groups"));
+        assert!(!remove(&mut table, &["access_token", "nested"]));
+        assert!(remove(&mut table, &["access_token"]));
+    }
+
+    #[test]
+    fn finds_unknown_keys() {
+        let table = toml::from_str::<Table>("acess_token = \"x\"\n[app]\napp_id = 1\nid = 2\n[groups]\nweb = []\n")
+            .unwrap();
+
+        assert_eq!(unknown_keys(&table, ""), vec!["acess_token", "app.id"]);
+    }
+
+    #[test]
+    fn locates_keys_and_tables() {
+        assert_eq!(locate_key(CONTENTS, "access_token"), Some((0, 0)));
+        assert_eq!(locate_key(CONTENTS, "app"), Some((2, 0)));
+        assert_eq!(locate_key(CONTENTS, "app.app_id"), Some((3, 0)));
+        assert_eq!(locate_key(CONTENTS, "groups.web"), Some((8, 0)));
+        assert_eq!(locate_key(CONTENTS, "app_id"), None);
+        assert_eq!(locate_key("# app_id = 1\n  [app]\n", "app"), Some((1, 2)));
+    }
+
+    #[test]
+    fn validates_files_reporting_the_position_of_each_problem() {
+        let dir = env::temp_dir().join(format!("ghtool-validate-{}", ::std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+
+        fs::write(&path, CONTENTS).unwrap();
+        assert!(validate_file(&path, false).unwrap().is_empty());
+
+        let problems = validate_file(&path, true).unwrap();
+        let positions = problems.iter().map(|problem| (problem.line, problem.column)).collect::<Vec<_>>();
+        assert_eq!(positions, vec![(Some(1), Some(1)), (Some(3), Some(1))]);
+
+        fs::write(&path, "[retry]\nmax_attempts = \"three\"\ntimeout = 1\n").unwrap();
+        let problems = validate_file(&path, false).unwrap();
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].message, "unknown key \"retry.timeout\"");
+        assert_eq!(problems[0].line, Some(3));
+
+        fs::write(&path, "access_token = \n").unwrap();
+        let problems = validate_file(&path, false).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert_eq!(problems[0].line, Some(1));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,983 @@
+//
+//  config/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 28/02/2018.
+//  Copyright © 2018 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `config` module implements functionality for reading configuration information from files on disk.
+//!
+//! Configuration is loaded from up to three files, each of which overrides the values in the ones before it:
+//!
+//! 1. A system-wide file at `/etc/ghtool/config.toml`.
+//! 2. The user's file. This is the path given by the `--config` argument, or the `GHTOOL_CONFIG` environment variable,
+//!    or `$XDG_CONFIG_HOME/ghtool/config.toml` (defaulting to `~/.config/ghtool/config.toml`), in that order.
+//! 3. A project-local `.ghtool.toml`, found by searching the working directory and each of its ancestors.
+//!
+//! A project-local file may be checked out along with a repository that isn't the user's own, so it can only contain
+//! the tables in `PROJECT_TABLES`. Any other keys in it, such as credentials, are ignored with a warning. Hosts named
+//! in it aren't trusted with credentials either: see `trusted_hosts`.
+
+pub mod command;
+
+use std::env;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::fs::{File, OpenOptions};
+use std::io;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use toml::value::{Table, Value};
+
+use crate::auth::{AppAuth, Auth};
+use crate::client::retry::RetryPolicy;
+use crate::util::error::RepoError;
+use crate::util::exit::ExitCode;
+use crate::util::git;
+use crate::util::output::Format;
+use crate::util::repo::{Repo, DEFAULT_HOST};
+use crate::util::selector::Groups;
+use crate::util::style::ColorChoice;
+
+/// The user's configuration, including values loaded from disk and values loaded from flags at runtime.
+#[derive(Debug)]
+pub struct Config {
+    pub auth: Auth,
+    pub defaults: Defaults,
+    pub git: GitConfig,
+    pub groups: Groups,
+    pub retry: RetryPolicy,
+    /// A file of extra certificates to trust when connecting to GitHub, from `ca_bundle` or the `--cacert` argument.
+    pub ca_bundle: Option<PathBuf>,
+    /// The format that commands print their results in, from the `--output` argument.
+    pub output: Format,
+    /// Whether to print text in colour, from the `--color` argument.
+    pub color: ColorChoice,
+    /// Whether to only print results and errors, from the `--quiet` argument.
+    pub quiet: bool,
+    /// The number of requests to send at once, from the `--jobs` argument.
+    pub jobs: usize,
+    /// Whether to keep responses from GitHub on disk and send conditional requests for them, unless the `--no-cache`
+    /// argument is given.
+    pub use_cache: bool,
+    /// The hosts of the GitHub instances that credentials may be sent to.
+    pub trusted_hosts: Vec<String>,
+}
+
+impl Config {
+    pub fn auth(&self) -> &Auth {
+        &self.auth
+    }
+
+    pub fn defaults(&self) -> &Defaults {
+        &self.defaults
+    }
+
+    pub fn groups(&self) -> &Groups {
+        &self.groups
+    }
+
+    pub fn retry(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    pub fn ca_bundle(&self) -> Option<&Path> {
+        self.ca_bundle.as_deref()
+    }
+
+    pub fn output(&self) -> Format {
+        self.output
+    }
+
+    pub fn color(&self) -> ColorChoice {
+        self.color
+    }
+
+    pub fn quiet(&self) -> bool {
+        self.quiet
+    }
+
+    pub fn jobs(&self) -> usize {
+        self.jobs
+    }
+
+    pub fn use_cache(&self) -> bool {
+        self.use_cache
+    }
+
+    pub fn trusted_hosts(&self) -> &[String] {
+        &self.trusted_hosts[..]
+    }
+
+    /// Returns the repository that commands act on when none is given. This is the value of `defaults.repo` if it's
+    /// set, or otherwise the GitHub repository that the git checkout containing the working directory was cloned from.
+    pub fn default_repo(&self) -> Option<Repo> {
+        self.defaults
+            .repo
+            .clone()
+            .or_else(|| git::infer_repo(&self.git.remotes[..], &self.git.hosts[..]))
+    }
+}
+
+/// Settings for working out which GitHub repository a git checkout belongs to.
+#[derive(Debug)]
+pub struct GitConfig {
+    /// The names of the remotes to look for a GitHub repository in, in order of preference.
+    pub remotes: Vec<String>,
+    /// The hosts of GitHub instances. Remotes pointing to any other host are ignored.
+    pub hosts: Vec<String>,
+}
+
+impl Default for GitConfig {
+    fn default() -> GitConfig {
+        GitConfig {
+            remotes: vec!["upstream".to_owned(), "origin".to_owned()],
+            hosts: vec![DEFAULT_HOST.to_owned()],
+        }
+    }
+}
+
+pub struct IntoConfig {
+    auth: Auth,
+    defaults: Defaults,
+    git: GitConfig,
+    groups: Groups,
+    retry: RetryPolicy,
+    ca_bundle: Option<PathBuf>,
+    output: Format,
+    color: ColorChoice,
+    quiet: bool,
+    jobs: usize,
+    use_cache: bool,
+    trusted_hosts: Vec<String>,
+}
+
+impl IntoConfig {
+    pub fn default(auth: Auth) -> IntoConfig {
+        IntoConfig {
+            auth,
+            defaults: Defaults::default(),
+            git: GitConfig::default(),
+            groups: Groups::new(),
+            retry: RetryPolicy::default(),
+            ca_bundle: None,
+            output: Format::default(),
+            color: ColorChoice::default(),
+            quiet: false,
+            jobs: 1,
+            use_cache: true,
+            trusted_hosts: vec![DEFAULT_HOST.to_owned()],
+        }
+    }
+
+    /// Creates an `IntoConfig` from the values in a `StoredConfig`. If `auth` is provided, it's used instead of any
+    /// credentials in the `StoredConfig`; otherwise, GitHub App credentials take precedence over a personal access
+    /// token if both are present.
+    pub fn from_stored(stored: StoredConfig, auth: Option<Auth>) -> Result<IntoConfig, ConfigError> {
+        let defaults = match stored.defaults {
+            Some(defaults) => Defaults::from_stored(defaults)?,
+            None => Defaults::default(),
+        };
+
+        let git = match stored.git {
+            Some(git) => GitConfig {
+                remotes: git.remotes.unwrap_or_else(|| GitConfig::default().remotes),
+                hosts: git.hosts.unwrap_or_else(|| GitConfig::default().hosts),
+            },
+            None => GitConfig::default(),
+        };
+
+        let retry = match stored.retry {
+            Some(retry) => {
+                let default = RetryPolicy::default();
+                RetryPolicy {
+                    max_attempts: retry.max_attempts.unwrap_or(default.max_attempts),
+                    initial_delay_ms: retry.initial_delay_ms.unwrap_or(default.initial_delay_ms),
+                    max_delay_ms: retry.max_delay_ms.unwrap_or(default.max_delay_ms),
+                    jitter: retry.jitter.unwrap_or(default.jitter),
+                }
+            }
+            None => RetryPolicy::default(),
+        };
+
+        let auth = match (auth, stored.app, stored.access_token) {
+            (Some(auth), _, _) => auth,
+            (None, Some(app), _) => Auth::App(app.into_auth()?),
+            (None, None, Some(access_token)) => Auth::Token(access_token),
+            (None, None, None) => return Err(ConfigError::MissingCredentials),
+        };
+
+        Ok(IntoConfig {
+            auth,
+            defaults,
+            git,
+            groups: stored.groups.unwrap_or_default(),
+            retry,
+            ca_bundle: stored.ca_bundle,
+            output: Format::default(),
+            color: ColorChoice::default(),
+            quiet: false,
+            jobs: 1,
+            use_cache: true,
+            trusted_hosts: vec![DEFAULT_HOST.to_owned()],
+        })
+    }
+
+    /// Sets the file of extra certificates to trust, if `ca_bundle` is `Some`, overriding any from the configuration
+    /// files.
+    pub fn ca_bundle(mut self, ca_bundle: Option<PathBuf>) -> IntoConfig {
+        if ca_bundle.is_some() {
+            self.ca_bundle = ca_bundle;
+        }
+
+        self
+    }
+
+    /// Sets the format that commands print their results in.
+    pub fn output(mut self, output: Format) -> IntoConfig {
+        self.output = output;
+        self
+    }
+
+    /// Sets whether to print text in colour.
+    pub fn color(mut self, color: ColorChoice) -> IntoConfig {
+        self.color = color;
+        self
+    }
+
+    /// Sets whether to only print results and errors.
+    pub fn quiet(mut self, quiet: bool) -> IntoConfig {
+        self.quiet = quiet;
+        self
+    }
+
+    /// Sets the number of requests to send at once.
+    pub fn jobs(mut self, jobs: usize) -> IntoConfig {
+        self.jobs = jobs;
+        self
+    }
+
+    /// Sets whether to keep responses from GitHub on disk.
+    pub fn use_cache(mut self, use_cache: bool) -> IntoConfig {
+        self.use_cache = use_cache;
+        self
+    }
+
+    /// Adds `hosts` to the hosts of the GitHub instances that credentials may be sent to, besides github.com.
+    pub fn trusted_hosts(mut self, hosts: Vec<String>) -> IntoConfig {
+        for host in hosts {
+            if !self.trusted_hosts.iter().any(|trusted| trusted.eq_ignore_ascii_case(&host[..])) {
+                self.trusted_hosts.push(host);
+            }
+        }
+
+        self
+    }
+
+    pub fn build(self) -> Config {
+        Config {
+            auth: self.auth,
+            defaults: self.defaults,
+            git: self.git,
+            groups: self.groups,
+            retry: self.retry,
+            ca_bundle: self.ca_bundle,
+            output: self.output,
+            color: self.color,
+            quiet: self.quiet,
+            jobs: self.jobs,
+            use_cache: self.use_cache,
+            trusted_hosts: self.trusted_hosts,
+        }
+    }
+}
+
+/// Default values for command-line arguments. These are typically set in a project-local configuration file, so that
+/// commands run inside a checkout of a repository act on that repository.
+#[derive(Debug, Default)]
+pub struct Defaults {
+    /// The repository that commands act on when none is given.
+    pub repo: Option<Repo>,
+    /// The path to the label manifest that label commands read when none is given.
+    pub label_manifest: Option<PathBuf>,
+    /// The owner of repositories given by name only, e.g. "web" rather than "acme/web".
+    pub org: Option<String>,
+}
+
+impl Defaults {
+    /// Creates a `Defaults` from the values in the `[defaults]` table of a configuration file.
+    pub fn from_stored(stored: StoredDefaults) -> Result<Defaults, ConfigError> {
+        let repo = match stored.repo {
+            Some(repo_string) => {
+                let default_owner = stored.org.as_ref().map(|org| &org[..]);
+                let repo = Repo::from_string_with_default_owner(&repo_string[..], default_owner).map_err(|err| {
+                    ConfigError::InvalidDefault {
+                        key: "defaults.repo",
+                        error: err,
+                    }
+                })?;
+                Some(repo)
+            }
+            None => None,
+        };
+
+        Ok(Defaults {
+            repo,
+            label_manifest: stored.label_manifest,
+            org: stored.org,
+        })
+    }
+}
+
+/// The user's configuration, loaded from disk where it is stored in TOML format.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StoredConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub access_token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ca_bundle: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app: Option<StoredAppConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub defaults: Option<StoredDefaults>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub git: Option<StoredGitConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub groups: Option<Groups>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry: Option<StoredRetryConfig>,
+}
+
+/// The details of a GitHub App installation, loaded from the `[app]` table of the user's configuration file.
+///
+/// `ghtool config set` sets one key at a time, so the table is allowed to be incomplete until the configuration is
+/// actually used.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct StoredAppConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub private_key_path: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub installation_id: Option<u64>,
+}
+
+impl StoredAppConfig {
+    /// Creates the credentials for the installation, failing with `ConfigError::IncompleteApp` if any of its details
+    /// are missing.
+    fn into_auth(self) -> Result<AppAuth, ConfigError> {
+        match (self.app_id, self.private_key_path, self.installation_id) {
+            (Some(app_id), Some(private_key_path), Some(installation_id)) => {
+                Ok(AppAuth::new(app_id, private_key_path, installation_id))
+            }
+            (None, _, _) => Err(ConfigError::IncompleteApp("app.app_id")),
+            (_, None, _) => Err(ConfigError::IncompleteApp("app.private_key_path")),
+            (_, _, None) => Err(ConfigError::IncompleteApp("app.installation_id")),
+        }
+    }
+}
+
+/// Default values for command-line arguments, loaded from the `[defaults]` table of the user's configuration file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StoredDefaults {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label_manifest: Option<PathBuf>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub org: Option<String>,
+}
+
+/// Settings for working out which GitHub repository a git checkout belongs to, loaded from the `[git]` table of the
+/// user's configuration file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StoredGitConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remotes: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosts: Option<Vec<String>>,
+}
+
+/// How to retry requests that fail, loaded from the `[retry]` table of the user's configuration file.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct StoredRetryConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initial_delay_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_delay_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jitter: Option<bool>,
+}
+
+impl StoredConfig {
+    /// Attempts to load the configuration files in `locations` and merge them into a single configuration.
+    pub fn try_load(locations: &Locations) -> Result<StoredConfig, ConfigError> {
+        merged_table(locations).and_then(|table| {
+            Value::Table(table)
+                .try_into::<StoredConfig>()
+                .map_err(ConfigError::InvalidValue)
+        })
+    }
+
+    /// Returns whether any of the configuration files in `locations` exist.
+    pub fn file_exists(locations: &Locations) -> bool {
+        !locations.existing().is_empty()
+    }
+
+    /// Serializes this configuration to TOML and writes it to the user's configuration file, replacing any existing
+    /// file. Returns the path to the file.
+    pub fn save(&self, locations: &Locations) -> Result<PathBuf, ConfigError> {
+        let path = locations.user.clone().ok_or(ConfigError::FileMissing)?;
+
+        toml::to_string(self)
+            .map_err(ConfigError::SerializeError)
+            .and_then(|contents| write_contents(&path, &contents[..]))
+            .and(Ok(path))
+    }
+}
+
+/// The locations of the configuration files that ghtool reads, in increasing order of precedence.
+#[derive(Clone, Debug)]
+pub struct Locations {
+    /// The system-wide configuration file.
+    pub system: Option<PathBuf>,
+    /// The user's configuration file. This is the file that `ghtool config` edits.
+    pub user: Option<PathBuf>,
+    /// The nearest project-local configuration file, if one was found.
+    pub project: Option<PathBuf>,
+}
+
+impl Locations {
+    /// Determines the locations of the configuration files. `explicit` is the path given by the `--config` argument,
+    /// if any, which takes the place of the user's configuration file.
+    pub fn discover(explicit: Option<&Path>) -> Locations {
+        Locations {
+            system: system_config_path(),
+            user: explicit.map(Path::to_path_buf).or_else(user_config_path),
+            project: project_config_path(),
+        }
+    }
+
+    /// Returns the paths of the configuration files that exist, in increasing order of precedence.
+    pub fn existing(&self) -> Vec<&Path> {
+        vec![&self.system, &self.user, &self.project]
+            .into_iter()
+            .filter_map(|path| path.as_ref())
+            .filter(|path| path.is_file())
+            .map(|path| path.as_path())
+            .collect()
+    }
+}
+
+/// The type of the value of a configuration key.
+#[derive(Clone, Copy, Debug)]
+pub enum ValueType {
+    String,
+    Integer,
+    Boolean,
+    /// A path, which is resolved relative to the directory containing the configuration file that it appears in.
+    Path,
+    StringArray,
+}
+
+impl fmt::Display for ValueType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ValueType::String => write!(f, "a string"),
+            ValueType::Integer => write!(f, "an integer"),
+            ValueType::Boolean => write!(f, "true or false"),
+            ValueType::Path => write!(f, "a path"),
+            ValueType::StringArray => write!(f, "a comma-separated list of strings"),
+        }
+    }
+}
+
+/// A key that may appear in the configuration file.
+pub struct Key {
+    /// The full, dot-separated name of the key, e.g. "app.app_id".
+    pub name: &'static str,
+    /// The type of the key's value.
+    pub value_type: ValueType,
+}
+
+/// Every key that may appear in the configuration file.
+pub const KEYS: &[Key] = &[
+    Key {
+        name: "access_token",
+        value_type: ValueType::String,
+    },
+    Key {
+        name: "ca_bundle",
+        value_type: ValueType::Path,
+    },
+    Key {
+        name: "app.app_id",
+        value_type: ValueType::Integer,
+    },
+    Key {
+        name: "app.private_key_path",
+        value_type: ValueType::Path,
+    },
+    Key {
+        name: "app.installation_id",
+        value_type: ValueType::Integer,
+    },
+    Key {
+        name: "defaults.repo",
+        value_type: ValueType::String,
+    },
+    Key {
+        name: "defaults.label_manifest",
+        value_type: ValueType::Path,
+    },
+    Key {
+        name: "defaults.org",
+        value_type: ValueType::String,
+    },
+    Key {
+        name: "git.remotes",
+        value_type: ValueType::StringArray,
+    },
+    Key {
+        name: "git.hosts",
+        value_type: ValueType::StringArray,
+    },
+    Key {
+        name: "retry.max_attempts",
+        value_type: ValueType::Integer,
+    },
+    Key {
+        name: "retry.initial_delay_ms",
+        value_type: ValueType::Integer,
+    },
+    Key {
+        name: "retry.max_delay_ms",
+        value_type: ValueType::Integer,
+    },
+    Key {
+        name: "retry.jitter",
+        value_type: ValueType::Boolean,
+    },
+    // Each key in the `[groups]` table is the name of a group.
+    Key {
+        name: "groups.*",
+        value_type: ValueType::StringArray,
+    },
+];
+
+/// Looks up the key with the full name `name`, returning `None` if it isn't a valid configuration key. A key whose
+/// name ends in ".*" matches any single name in that table, e.g. "groups.*" matches "groups.frontend".
+pub fn find_key(name: &str) -> Option<&'static Key> {
+    KEYS.iter().find(|key| {
+        if key.name.ends_with(".*") {
+            let table = &key.name[..key.name.len() - 1];
+            name.starts_with(table) && !name[table.len()..].is_empty() && !name[table.len()..].contains('.')
+        } else {
+            key.name == name
+        }
+    })
+}
+
+/// Returns whether `name` is the name of a table that contains at least one valid configuration key.
+pub fn is_table(name: &str) -> bool {
+    KEYS.iter().any(|key| key.name.starts_with(name) && key.name[name.len()..].starts_with('.'))
+}
+
+/// The name of the project-local configuration file.
+const PROJECT_CONFIG_FILE_NAME: &str = ".ghtool.toml";
+
+/// The tables that may appear in a project-local configuration file. Everything else, such as credentials or the CA
+/// bundle, can only be set in the system-wide or user configuration files.
+pub const PROJECT_TABLES: &[&str] = &["defaults", "git", "groups"];
+
+/// Returns whether the key with the full name `name` may appear in a project-local configuration file.
+pub fn is_project_key(name: &str) -> bool {
+    let table = name.split('.').next().unwrap_or(name);
+    PROJECT_TABLES.contains(&table)
+}
+
+/// Get the path to the system-wide ghtool config file, or `None` if there isn't one on this platform.
+#[cfg(unix)]
+fn system_config_path() -> Option<PathBuf> {
+    Some(PathBuf::from("/etc/ghtool/config.toml"))
+}
+
+/// Get the path to the system-wide ghtool config file, or `None` if there isn't one on this platform.
+#[cfg(not(unix))]
+fn system_config_path() -> Option<PathBuf> {
+    None
+}
+
+/// Get the path to the user's ghtool config file, or `None` if it isn't possible to determine their home directory.
+fn user_config_path() -> Option<PathBuf> {
+    if let Some(path) = env::var_os("GHTOOL_CONFIG") {
+        return Some(PathBuf::from(path));
+    }
+
+    // The XDG Base Directory specification says that relative paths in XDG_CONFIG_HOME should be ignored.
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::home_dir().map(|home| home.join(".config")))
+        .map(|config_dir| config_dir.join("ghtool").join("config.toml"))
+}
+
+/// Get the path to the nearest project-local config file, searching upwards from the working directory.
+fn project_config_path() -> Option<PathBuf> {
+    env::current_dir().ok().and_then(|dir| {
+        dir.ancestors()
+            .map(|ancestor| ancestor.join(PROJECT_CONFIG_FILE_NAME))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Get the contents of the config file at `path` as a `String`, if possible.
+pub fn read_contents(path: &Path) -> Result<String, ConfigError> {
+    File::open(path)
+        .map_err(ConfigError::IoError)
+        .and_then(|mut file| {
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)
+                .map_err(ConfigError::IoError)
+                .and(Ok(contents))
+        })
+}
+
+/// Read and parse the config file at `path` as a TOML table, without interpreting its contents.
+pub fn read_table(path: &Path) -> Result<Table, ConfigError> {
+    read_contents(path).and_then(|contents| {
+        toml::from_str::<Table>(&contents[..]).map_err(|err| ConfigError::ParseError(path.to_path_buf(), err))
+    })
+}
+
+/// Replace the contents of the config file at `path` with `contents`, creating the file and its parent directories if
+/// necessary. The file can contain an access token, so only the user is allowed to read or write it.
+pub fn write_contents(path: &Path, contents: &str) -> Result<(), ConfigError> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(ConfigError::IoError)?;
+    }
+
+    let mut options = OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    owner_only(&mut options);
+
+    options
+        .open(path)
+        .and_then(|mut file| {
+            // Files that already existed keep their permissions when they're opened, so tighten them as well.
+            restrict_permissions(&file)?;
+            file.write_all(contents.as_bytes())
+        })
+        .map_err(ConfigError::IoError)
+}
+
+/// Makes files created with `options` readable and writable only by their owner.
+#[cfg(unix)]
+fn owner_only(options: &mut OpenOptions) {
+    use std::os::unix::fs::OpenOptionsExt;
+
+    options.mode(0o600);
+}
+
+/// Makes files created with `options` readable and writable only by their owner.
+#[cfg(not(unix))]
+fn owner_only(_options: &mut OpenOptions) {}
+
+/// Makes `file` readable and writable only by its owner.
+#[cfg(unix)]
+fn restrict_permissions(file: &File) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    file.set_permissions(fs::Permissions::from_mode(0o600))
+}
+
+/// Makes `file` readable and writable only by its owner.
+#[cfg(not(unix))]
+fn restrict_permissions(_file: &File) -> io::Result<()> {
+    Ok(())
+}
+
+/// Read every config file in `locations` and merge them into a single TOML table.
+pub fn merged_table(locations: &Locations) -> Result<Table, ConfigError> {
+    let paths = locations.existing();
+    if paths.is_empty() {
+        return Err(ConfigError::FileMissing);
+    }
+
+    let mut merged = Table::new();
+    for path in paths {
+        debug!("Loading configuration file {}", path.display());
+
+        let mut table = read_table(path)?;
+        if locations.project.as_ref().is_some_and(|project| project == path) {
+            remove_non_project_keys(&mut table, path);
+        }
+
+        if let Some(dir) = path.parent() {
+            resolve_relative_paths(&mut table, dir);
+        }
+
+        merge(&mut merged, table);
+    }
+
+    Ok(merged)
+}
+
+/// Returns the hosts named in the system-wide and user's configuration files, in `git.hosts`, `defaults.repo` or the
+/// members of a group, which are trusted with credentials as well as github.com.
+///
+/// Hosts named in a project-local configuration file aren't included: otherwise, running ghtool in a checkout of
+/// someone else's repository could send the user's credentials to a server of their choosing.
+pub fn trusted_hosts(locations: &Locations) -> Vec<String> {
+    let paths = vec![&locations.system, &locations.user]
+        .into_iter()
+        .filter_map(|path| path.as_ref())
+        .filter(|path| path.is_file());
+
+    let mut hosts = Vec::new();
+    for path in paths {
+        let table = match read_table(path) {
+            Ok(table) => table,
+            Err(_) => continue,
+        };
+
+        let git_hosts = table
+            .get("git")
+            .and_then(|git| git.get("hosts"))
+            .and_then(Value::as_array)
+            .map(|hosts| hosts.iter().filter_map(Value::as_str).map(str::to_owned).collect::<Vec<_>>())
+            .unwrap_or_default();
+        hosts.extend(git_hosts);
+
+        let default_repo = table.get("defaults").and_then(|defaults| defaults.get("repo"));
+        let members = table
+            .get("groups")
+            .and_then(Value::as_table)
+            .map(|groups| groups.values().filter_map(Value::as_array).flatten().collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        // Selectors for groups and files don't name a host themselves.
+        let repo_hosts = default_repo
+            .into_iter()
+            .chain(members)
+            .filter_map(Value::as_str)
+            .filter(|string| !string.starts_with(':') && !string.starts_with('@'))
+            .filter_map(|string| Repo::from_string(string).ok())
+            .map(|repo| repo.host);
+        hosts.extend(repo_hosts);
+    }
+
+    hosts
+}
+
+/// Remove every key from `table`, which was read from the project-local configuration file at `path`, that may not
+/// appear in a project-local configuration file, warning about each one.
+fn remove_non_project_keys(table: &mut Table, path: &Path) {
+    let keys = table.keys().filter(|key| !is_project_key(key)).cloned().collect::<Vec<_>>();
+
+    for key in keys {
+        warn!("Ignoring \"{}\" in {}: it can't be set in a project-local configuration file", key, path.display());
+        table.remove(&key);
+    }
+}
+
+/// Make the value of every path-valued key in `table` that is a relative path relative to `dir` instead, so that paths
+/// in a configuration file are relative to the directory containing it rather than the working directory.
+fn resolve_relative_paths(table: &mut Table, dir: &Path) {
+    for key in KEYS.iter().filter(|key| matches!(key.value_type, ValueType::Path)) {
+        let mut segments = key.name.split('.');
+        let first = segments.next().and_then(|segment| table.get_mut(segment));
+        let value = segments.fold(first, |value, segment| value.and_then(|value| value.get_mut(segment)));
+
+        if let Some(value) = value {
+            let resolved = match *value {
+                Value::String(ref path) if Path::new(path).is_relative() => dir.join(path),
+                _ => continue,
+            };
+
+            *value = Value::String(resolved.to_string_lossy().into_owned());
+        }
+    }
+}
+
+/// Merge `overlay` into `base`. Tables present in both are merged recursively; any other value in `overlay` replaces
+/// the corresponding value in `base`.
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        if let Value::Table(overlay_table) = value {
+            if let Some(&mut Value::Table(ref mut base_table)) = base.get_mut(&key) {
+                merge(base_table, overlay_table);
+                continue;
+            }
+
+            base.insert(key, Value::Table(overlay_table));
+        } else {
+            base.insert(key, value);
+        }
+    }
+}
+
+/// Errors that arise in the process of reading or writing the user's config file.
+#[derive(Debug)]
+pub enum ConfigError {
+    FileMissing,
+    MissingCredentials,
+    /// The `[app]` table doesn't have the key with this name.
+    IncompleteApp(&'static str),
+    IoError(io::Error),
+    ParseError(PathBuf, toml::de::Error),
+    InvalidValue(toml::de::Error),
+    InvalidDefault { key: &'static str, error: RepoError },
+    SerializeError(toml::ser::Error),
+    /// The file of extra certificates to trust couldn't be read, or doesn't contain PEM-encoded certificates.
+    InvalidCaBundle { path: PathBuf, reason: String },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConfigError::FileMissing => write!(f, "No configuration file found; run `ghtool config init` to create one"),
+            ConfigError::MissingCredentials => write!(
+                f,
+                "The configuration file contains neither `access_token` nor an `[app]` table"
+            ),
+            ConfigError::IncompleteApp(key) => {
+                write!(f, "The [app] table of the configuration file doesn't set \"{}\"", key)
+            }
+            ConfigError::IoError(ref err) => write!(f, "Unable to access the configuration file: {}", err),
+            ConfigError::ParseError(ref path, ref err) => match err.line_col() {
+                Some((line, col)) => write!(
+                    f,
+                    "Unable to parse {} at line {}, column {}: {}",
+                    path.display(),
+                    line + 1,
+                    col + 1,
+                    err
+                ),
+                None => write!(f, "Unable to parse {}: {}", path.display(), err),
+            },
+            ConfigError::InvalidValue(ref err) => write!(f, "Invalid configuration: {}", err),
+            ConfigError::InvalidDefault { ref key, ref error } => {
+                write!(f, "Invalid value for configuration key \"{}\": {}", key, error)
+            }
+            ConfigError::SerializeError(ref err) => write!(f, "Unable to write the configuration file: {}", err),
+            ConfigError::InvalidCaBundle { ref path, ref reason } => {
+                write!(f, "Unable to read certificates from {}: {}", path.display(), reason)
+            }
+        }
+    }
+}
+
+impl ConfigError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::Config
+    }
+}
+
+impl Error for ConfigError {
+    fn description(&self) -> &str {
+        match *self {
+            ConfigError::FileMissing => "The configuration file does not exist.",
+            ConfigError::MissingCredentials => {
+                "The configuration file contains neither an access token nor GitHub App credentials."
+            }
+            ConfigError::IncompleteApp(_) => "Incomplete GitHub App credentials.",
+            ConfigError::IoError(_) => "An IO error occurred.",
+            ConfigError::ParseError(..) => "Unable to parse configuration file.",
+            ConfigError::InvalidValue(_) => "Invalid configuration value.",
+            ConfigError::InvalidDefault { .. } => "Invalid default value.",
+            ConfigError::SerializeError(_) => "Unable to serialize configuration file.",
+            ConfigError::InvalidCaBundle { .. } => "Invalid CA bundle.",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Creates an empty directory for a test to write configuration files in.
+    fn scratch_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("ghtool-config-{}-{}", ::std::process::id(), name));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[cfg(unix)]
+    fn mode(path: &Path) -> u32 {
+        use std::os::unix::fs::PermissionsExt;
+
+        fs::metadata(path).unwrap().permissions().mode() & 0o777
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn creates_files_readable_only_by_their_owner() {
+        let dir = scratch_dir("create");
+        let path = dir.join("ghtool").join("config.toml");
+
+        write_contents(&path, "access_token = \"secret\"\n").unwrap();
+
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(read_contents(&path).unwrap(), "access_token = \"secret\"\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn tightens_the_permissions_of_existing_files() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = scratch_dir("tighten");
+        let path = dir.join("config.toml");
+        fs::write(&path, "access_token = \"old\"\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        write_contents(&path, "access_token = \"new\"\n").unwrap();
+
+        assert_eq!(mode(&path), 0o600);
+        assert_eq!(read_contents(&path).unwrap(), "access_token = \"new\"\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn finds_keys_in_tables_with_any_name() {
+        assert!(find_key("app.app_id").is_some());
+        assert!(find_key("groups.frontend").is_some());
+        assert!(find_key("groups").is_none());
+        assert!(find_key("groups.frontend.extra").is_none());
+        assert!(find_key("app.unknown").is_none());
+        assert!(is_table("retry"));
+        assert!(!is_table("access_token"));
+    }
+
+    #[test]
+    fn only_allows_defaults_git_and_groups_in_project_files() {
+        let contents = "access_token = \"secret\"\n[app]\napp_id = 1\n\
+                        [defaults]\nrepo = \"acme/web\"\n[groups]\nweb = []\n";
+        let mut table = toml::from_str::<Table>(contents).unwrap();
+
+        remove_non_project_keys(&mut table, Path::new(".ghtool.toml"));
+
+        assert_eq!(table.keys().collect::<Vec<_>>(), vec!["defaults", "groups"]);
+    }
+}
@@ -0,0 +1,322 @@
+//
+//  errors.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The errors that stop a command from running.
+//!
+//! Every command returns this `Error`, which wraps the more specific error types from the rest of the crate. As well as
+//! a message and the chain of errors that caused it, an `Error` knows the HTTP status and message of any error response
+//! from GitHub, the code that ghtool should exit with, and a hint about how to fix it, if there is one.
+
+use chrono::{Local, TimeZone};
+use reqwest::StatusCode;
+
+use std::io;
+use std::path::PathBuf;
+
+use crate::auth::error::AuthError;
+use crate::config::{ConfigError, ValueType};
+use crate::util::error::{ArgError, RestError, SelectorError};
+use crate::util::exit::ExitCode;
+use crate::util::output::Format;
+use crate::util::rest;
+use crate::util::style::{ColorChoice, Style};
+
+error_chain! {
+    foreign_links {
+        Io(io::Error);
+        Arg(ArgError);
+        Config(ConfigError);
+        Auth(AuthError);
+        Selector(SelectorError);
+    }
+
+    errors {
+        NoSubcommand {
+            description("no subcommand provided")
+            display("No subcommand provided")
+        }
+        // GitHub responded to a request with an error. `message` is the message from the body of the response.
+        Api { status: StatusCode, message: String } {
+            description("GitHub returned an error")
+            display("GitHub returned {}: {}", status, message)
+        }
+        // The GitHub API rate limit has been exhausted, and won't be lifted until `reset`, in seconds since the Unix
+        // epoch.
+        RateLimited(reset: u64) {
+            description("rate limit exhausted")
+            display("The GitHub API rate limit has been exhausted until {}", format_time(*reset))
+        }
+        // A request to GitHub couldn't be made, or its response couldn't be read.
+        Request {
+            description("unable to make a request to GitHub")
+            display("Unable to make a request to GitHub")
+        }
+        // GitHub's response couldn't be decoded, so trying the request again would most likely fail the same way.
+        InvalidResponse {
+            description("invalid response from GitHub")
+            display("GitHub sent a response that couldn't be understood")
+        }
+        // A repository is on a host that credentials mustn't be sent to, because it was only named in a project-local
+        // configuration file or by a git remote.
+        UntrustedHost(host: String) {
+            description("untrusted host")
+            display("Not sending credentials to {}, which isn't a trusted GitHub instance", host)
+        }
+        // Some of the items that a command acts on failed, but not all of them.
+        // e.g. "Failed to copy 2 of 10 labels".
+        PartialFailure { verb: &'static str, failed: usize, total: usize, noun: &'static str } {
+            description("some items failed")
+            display("Failed to {} {} of {} {}", verb, failed, total, noun)
+        }
+        // `label copy --resume` was run, but there are no labels left over from a copy that was stopped by the rate
+        // limit.
+        NothingToResume {
+            description("nothing to resume")
+            display("There are no labels left to copy from a previous `label copy`")
+        }
+        // A label given with `label copy --label` isn't in the repository being copied from.
+        LabelNotFound { repo: String, name: String } {
+            description("label not found")
+            display("There is no label \"{}\" in {}", name, repo)
+        }
+        // The label manifest at `path` couldn't be read, or isn't a valid manifest because of `reason`.
+        InvalidManifest { path: PathBuf, reason: String } {
+            description("invalid label manifest")
+            display("Unable to read the label manifest {}: {}", path.display(), reason)
+        }
+        // `undo` or `history` was given a run that isn't in the journal.
+        UnknownRun(run: u64) {
+            description("unknown run")
+            display("There is no run {} in the journal", run)
+        }
+        // A change recorded in the journal can't be undone, because of `reason`.
+        // e.g. "The label has been changed since".
+        UndoConflict(reason: &'static str) {
+            description("change can't be undone")
+            display("{}", reason)
+        }
+        // The configuration file already exists, and `--force` wasn't given.
+        AlreadyExists(path: PathBuf) {
+            description("configuration file already exists")
+            display("A configuration file already exists at {}", path.display())
+        }
+        UnknownKey(key: String) {
+            description("unknown configuration key")
+            display("Unknown configuration key \"{}\"", key)
+        }
+        KeyNotSet(key: String) {
+            description("configuration key not set")
+            display("Configuration key \"{}\" is not set", key)
+        }
+        // `value` can't be parsed as the type of value expected by `key`.
+        InvalidValue { key: String, value: String, expected: ValueType } {
+            description("invalid configuration value")
+            display("Invalid value \"{}\" for configuration key \"{}\" (expected {})", value, key, expected)
+        }
+        // `ghtool config validate` found `count` problems with the configuration files.
+        InvalidConfig(count: usize) {
+            description("invalid configuration")
+            display("Found {} problem(s) in the configuration files", count)
+        }
+    }
+}
+
+impl From<RestError> for Error {
+    fn from(rest_err: RestError) -> Error {
+        let kind = match rest_err {
+            RestError::AuthError(auth_err) => return Error::from(auth_err),
+            RestError::StatusError { status, ref body } => ErrorKind::Api {
+                status,
+                message: rest::error_message(&body[..]),
+            },
+            RestError::JsonError(_) => ErrorKind::InvalidResponse,
+            _ => ErrorKind::Request,
+        };
+
+        Error::with_chain(rest_err, kind)
+    }
+}
+
+impl Error {
+    /// Returns the HTTP status and message of the error response from GitHub that caused this error, if there was one.
+    pub fn status(&self) -> Option<(StatusCode, String)> {
+        match *self.kind() {
+            ErrorKind::Api { status, ref message } => Some((status, message.clone())),
+            ErrorKind::Auth(AuthError::StatusError { status, ref body }) => {
+                Some((status, rest::error_message(&body[..])))
+            }
+            _ => None,
+        }
+    }
+
+    /// Returns the code that ghtool should exit with because of this error.
+    pub fn exit_code(&self) -> ExitCode {
+        match *self.kind() {
+            ErrorKind::Arg(ref arg_err) => arg_err.exit_code(),
+            ErrorKind::Config(ref config_err) => config_err.exit_code(),
+            ErrorKind::Selector(ref selector_err) => selector_err.exit_code(),
+            ErrorKind::Auth(_) => ExitCode::Auth,
+            ErrorKind::Api { status, ref message } => ExitCode::from_status(status, &message[..]),
+            ErrorKind::RateLimited(_) => ExitCode::RateLimited,
+            ErrorKind::PartialFailure { .. } => ExitCode::PartialFailure,
+            ErrorKind::NoSubcommand | ErrorKind::UnknownKey(_) | ErrorKind::InvalidValue { .. } => ExitCode::Usage,
+            ErrorKind::InvalidManifest { .. } => ExitCode::Usage,
+            ErrorKind::KeyNotSet(_) | ErrorKind::NothingToResume | ErrorKind::UnknownRun(_) => ExitCode::NotFound,
+            ErrorKind::LabelNotFound { .. } => ExitCode::NotFound,
+            ErrorKind::InvalidConfig(_) | ErrorKind::UntrustedHost(_) => ExitCode::Config,
+            _ => ExitCode::Failure,
+        }
+    }
+
+    /// Returns a suggestion for how to fix this error, if there is one.
+    pub fn hint(&self) -> Option<&'static str> {
+        if let Some((status, message)) = self.status() {
+            return match status {
+                StatusCode::UNAUTHORIZED => Some(
+                    "The credentials were rejected. Check that the access token is correct and hasn't expired or \
+                     been revoked, or create a new one at https://github.com/settings/tokens.",
+                ),
+                StatusCode::FORBIDDEN | StatusCode::TOO_MANY_REQUESTS
+                    if ExitCode::from_status(status, &message[..]) == ExitCode::RateLimited =>
+                {
+                    Some("The GitHub API rate limit has been exhausted. Wait for it to reset, then try again.")
+                }
+                StatusCode::FORBIDDEN => Some(
+                    "The credentials don't grant permission to do this. Personal access tokens need the `repo` scope \
+                     (or `public_repo` for public repositories), and GitHub Apps need the \"Issues\" permission.",
+                ),
+                StatusCode::NOT_FOUND => Some(
+                    "Check that the repository exists. GitHub also responds with 404 Not Found for private \
+                     repositories that the credentials can't access, so check that the token has the `repo` scope.",
+                ),
+                StatusCode::UNPROCESSABLE_ENTITY => Some(
+                    "GitHub rejected the request as invalid. If a label is being created, it may already exist.",
+                ),
+                _ => None,
+            };
+        }
+
+        match *self.kind() {
+            ErrorKind::Config(ConfigError::FileMissing) => Some(
+                "Create a configuration file with `ghtool config init`, or provide an access token with --token.",
+            ),
+            ErrorKind::Config(ConfigError::MissingCredentials) => Some(
+                "Set an access token with `ghtool config set access_token <TOKEN>`, or add an [app] table with the \
+                 details of a GitHub App installation.",
+            ),
+            ErrorKind::Config(ConfigError::IncompleteApp(_)) => Some(
+                "A GitHub App installation needs app.app_id, app.private_key_path and app.installation_id. Set the \
+                 missing one with `ghtool config set`.",
+            ),
+            ErrorKind::Config(ConfigError::InvalidCaBundle { .. }) => Some(
+                "Check that ca_bundle in the configuration files, or --cacert, points to a file of PEM-encoded \
+                 certificates.",
+            ),
+            ErrorKind::Config(_) | ErrorKind::InvalidConfig(_) => {
+                Some("Run `ghtool config validate` to find every problem in the configuration files.")
+            }
+            ErrorKind::RateLimited(_) => {
+                Some("Wait for the rate limit to reset, then try again. Use -v to see how many requests are left.")
+            }
+            ErrorKind::NothingToResume => {
+                Some("Labels are only left to copy when `label copy` stops because the rate limit was exhausted.")
+            }
+            ErrorKind::LabelNotFound { .. } => Some("Use `ghtool label list` to see the labels in the repository."),
+            ErrorKind::InvalidManifest { .. } => Some(
+                "A label manifest has a [[labels]] table for each label, with its name, its colour as six hexadecimal \
+                 digits and, optionally, its description.",
+            ),
+            ErrorKind::UnknownRun(_) => Some("Use `ghtool history` to see the runs in the journal."),
+            ErrorKind::AlreadyExists(_) => Some("Use --force to overwrite it."),
+            ErrorKind::UntrustedHost(_) => Some(
+                "Add the host to git.hosts in the user's configuration file, or give the repository as an argument. \
+                 Hosts named in a project-local .ghtool.toml are never trusted on their own.",
+            ),
+            ErrorKind::UnknownKey(_) => Some("The README lists every configuration key."),
+            ErrorKind::Auth(AuthError::IoError(_)) | ErrorKind::Auth(AuthError::PemError(_)) => Some(
+                "Check that app.private_key_path points to the private key downloaded from the GitHub App's \
+                 settings page.",
+            ),
+            ErrorKind::Request => Some("Check your network connection, then try again."),
+            _ => None,
+        }
+    }
+
+    /// Prints this error to stderr in the format `format`, along with its causes and a hint, if there is one. `color`
+    /// determines whether the text format is printed in colour.
+    pub fn report(&self, format: Format, color: ColorChoice) {
+        let causes = self.iter().skip(1).map(|cause| cause.to_string()).collect::<Vec<_>>();
+
+        match format {
+            Format::Text => {
+                let style = Style::stderr(color);
+
+                eprintln!("{} {}", style.error("error:"), style.bold(&self.to_string()[..]));
+                for cause in &causes {
+                    eprintln!("  {} {}", style.dim("caused by:"), cause);
+                }
+
+                if let Some(hint) = self.hint() {
+                    eprintln!("  {} {}", style.hint("hint:"), hint);
+                }
+            }
+            Format::Json => {
+                let status = self.status();
+                let report = ErrorReport {
+                    error: self.to_string(),
+                    causes,
+                    status: status.as_ref().map(|&(status, _)| u16::from(status)),
+                    message: status.map(|(_, message)| message),
+                    hint: self.hint(),
+                    exit_code: self.exit_code().code(),
+                };
+
+                match serde_json::to_string(&report) {
+                    Ok(json) => eprintln!("{}", json),
+                    Err(_) => eprintln!("{}", self),
+                }
+            }
+        }
+    }
+}
+
+/// Formats `timestamp`, in seconds since the Unix epoch, as a local date and time.
+pub fn format_time(timestamp: u64) -> String {
+    match Local.timestamp_opt(timestamp as i64, 0).single() {
+        Some(time) => time.format("%Y-%m-%d %H:%M:%S").to_string(),
+        None => timestamp.to_string(),
+    }
+}
+
+/// An error, as printed in the JSON format.
+#[derive(Serialize)]
+struct ErrorReport {
+    error: String,
+    causes: Vec<String>,
+    /// The HTTP status of the error response from GitHub, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    status: Option<u16>,
+    /// The message from the body of the error response from GitHub, if there was one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    hint: Option<&'static str>,
+    exit_code: i32,
+}
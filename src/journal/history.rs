@@ -0,0 +1,187 @@
+//
+//  journal/history.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `history` command, which shows the runs recorded in the journal, or the changes made by one of them.
+
+use chrono::{DateTime, Utc};
+use clap::ArgMatches;
+
+use super::{Change, Run};
+use crate::errors::{self, ErrorKind, Result};
+use crate::util::output::{self, Format};
+use crate::util::style::{self, Cell, ColorChoice, Style, Table};
+
+/// A run, as printed in the JSON format.
+#[derive(Serialize)]
+struct RunOutput<'r> {
+    run: u64,
+    time: DateTime<Utc>,
+    command: &'r str,
+    changes: usize,
+    repos: Vec<&'r str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    undoes: Option<u64>,
+    /// The runs of `undo` that have undone this run.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    undone_by: Vec<u64>,
+}
+
+/// Runs the `history` command, printing results in `output`.
+pub fn run(output: Format, color: ColorChoice, matches: &ArgMatches) -> Result<()> {
+    let runs = super::runs()?;
+
+    match matches.value_of("run").and_then(|run| run.parse::<u64>().ok()) {
+        Some(id) => {
+            let run = runs.iter().find(|run| run.id == id).ok_or(ErrorKind::UnknownRun(id))?;
+            show(output, color, run);
+        }
+        None => list(output, color, &runs[..]),
+    }
+
+    Ok(())
+}
+
+/// Prints a line for each of `runs`.
+fn list(output: Format, color: ColorChoice, runs: &[Run]) {
+    let style = Style::stdout(color);
+    let mut table = Table::new();
+
+    for run in runs {
+        let undone_by = runs
+            .iter()
+            .filter(|other| other.undoes() == Some(run.id))
+            .map(|other| other.id)
+            .collect::<Vec<_>>();
+
+        if output.is_json() {
+            output::print_json(&RunOutput {
+                run: run.id,
+                time: run.time(),
+                command: run.command(),
+                changes: run.entries.len(),
+                repos: run.repos(),
+                undoes: run.undoes(),
+                undone_by,
+            });
+            continue;
+        }
+
+        let mut command = run.command().to_owned();
+        if let Some(undoes) = run.undoes() {
+            command.push_str(&format!(" {}", undoes)[..]);
+        }
+
+        let mut summary = format!("{} change(s) in {}", run.entries.len(), run.repos().join(", "));
+        if let Some(last) = undone_by.last() {
+            summary.push_str(&format!(" (undone by run {})", last)[..]);
+        }
+
+        table.push(vec![
+            Cell::from(&run.id.to_string()[..]),
+            style.dim(&errors::format_time(run.time().timestamp() as u64)[..]),
+            Cell::from(&command[..]),
+            Cell::from(&summary[..]),
+        ]);
+    }
+
+    if !output.is_json() {
+        table.print();
+    }
+}
+
+/// Prints each change made by `run`.
+fn show(output: Format, color: ColorChoice, run: &Run) {
+    // In the JSON format, each change is printed on its own line, just as it's recorded in the journal.
+    if output.is_json() {
+        for entry in &run.entries {
+            output::print_json(entry);
+        }
+
+        return;
+    }
+
+    let style = Style::stdout(color);
+    println!("Run {}: {} at {}", run.id, run.command(), errors::format_time(run.time().timestamp() as u64));
+
+    for entry in &run.entries {
+        match entry.change {
+            Change::Label {
+                ref name,
+                ref before,
+                ref after,
+            } => {
+                let (marker, verb) = match (before, after) {
+                    (None, _) => (style::Change::Create, "Created"),
+                    (_, None) => (style::Change::Delete, "Deleted"),
+                    (Some(_), Some(_)) => (style::Change::Update, "Edited"),
+                };
+
+                let color = after.as_ref().or(before.as_ref()).map_or("", |label| &label.color[..]);
+                println!("{} {} label {} in {}", style.marker(marker), verb, style.label(&name[..], color), entry.repo);
+            }
+        }
+    }
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "history"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Show the changes that ghtool has made on GitHub"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            // <RUN>
+            Arg::with_name("run")
+                .value_name("RUN")
+                .help("The number of a run to show each change made by, instead of listing every run")
+                .validator(super::super::undo::details::validate_run)
+                .index(1),
+        ]
+    }
+}
@@ -0,0 +1,252 @@
+//
+//  journal/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The journal of every change that ghtool has made on GitHub, which `history` shows and `undo` reverts.
+//!
+//! The journal is kept in `$XDG_DATA_HOME/ghtool/journal.jsonl`, with one JSON object on each line for each change,
+//! appended as soon as the change has been made. Every change made by one run of a command shares the same run number,
+//! which is one more than the highest run number in the journal when the run made its first change. The journal is
+//! locked while the run number is chosen and the first change is written, so that runs happening at the same time never
+//! share a run number.
+
+pub mod history;
+pub mod undo;
+
+use chrono::{DateTime, Utc};
+
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, ErrorKind, Write};
+use std::path::PathBuf;
+
+use crate::client::Label;
+use crate::util::dirs;
+use crate::util::repo::Repo;
+
+/// A single change recorded in the journal.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Entry {
+    /// The number of the run that made the change.
+    pub run: u64,
+    pub time: DateTime<Utc>,
+    /// The command that made the change, e.g. "label copy".
+    pub command: String,
+    /// The run that the change was made to undo, if it was made by `undo`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub undoes: Option<u64>,
+    /// The repository that was changed, in the format "owner/repository".
+    pub repo: String,
+    #[serde(flatten)]
+    pub change: Change,
+}
+
+/// What was changed, and how.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "object", rename_all = "snake_case")]
+pub enum Change {
+    /// A label was created, deleted or edited. `before` is `None` if it was created, and `after` is `None` if it was
+    /// deleted.
+    Label {
+        name: String,
+        before: Option<Label>,
+        after: Option<Label>,
+    },
+}
+
+/// Every change made by one run of a command.
+#[derive(Debug)]
+pub struct Run {
+    pub id: u64,
+    pub entries: Vec<Entry>,
+}
+
+impl Run {
+    /// The time that the run made its first change.
+    pub fn time(&self) -> DateTime<Utc> {
+        self.entries[0].time
+    }
+
+    /// The command that made the changes.
+    pub fn command(&self) -> &str {
+        &self.entries[0].command[..]
+    }
+
+    /// The run that this run undid, if it was a run of `undo`.
+    pub fn undoes(&self) -> Option<u64> {
+        self.entries[0].undoes
+    }
+
+    /// The repositories that the run changed, in the order it first changed them.
+    pub fn repos(&self) -> Vec<&str> {
+        let mut repos: Vec<&str> = Vec::new();
+        for entry in &self.entries {
+            if !repos.contains(&&entry.repo[..]) {
+                repos.push(&entry.repo[..]);
+            }
+        }
+
+        repos
+    }
+}
+
+/// Records the changes made by one run of a command in the journal.
+///
+/// The journal is only a record of what happened, so failing to write to it doesn't stop the command: a warning is
+/// logged, and nothing else is recorded for the rest of the run.
+pub struct Journal {
+    command: String,
+    undoes: Option<u64>,
+    /// The journal file and the number of this run, once the first change has been recorded.
+    open: Option<(File, u64)>,
+    failed: bool,
+}
+
+impl Journal {
+    /// Starts recording the changes made by `command`.
+    pub fn start(command: &str) -> Journal {
+        Journal {
+            command: command.to_owned(),
+            undoes: None,
+            open: None,
+            failed: false,
+        }
+    }
+
+    /// Starts recording the changes made by `undo` to undo the run `run`.
+    pub fn start_undo(run: u64) -> Journal {
+        Journal {
+            undoes: Some(run),
+            ..Journal::start("undo")
+        }
+    }
+
+    /// Records `change`, which has just been made to `repo`.
+    pub fn record(&mut self, repo: &Repo, change: Change) {
+        if self.failed {
+            return;
+        }
+
+        if let Err(err) = self.append(repo, change) {
+            warn!("Unable to record a change to {} in the journal: {}", repo, err);
+            self.failed = true;
+        }
+    }
+
+    fn append(&mut self, repo: &Repo, change: Change) -> io::Result<()> {
+        if let Some(run) = self.open.as_ref().map(|&(_, run)| run) {
+            let entry = self.entry(run, repo, change);
+            let (ref mut file, _) = *self.open.as_mut().expect("journal is open");
+            return write_entry(file, &entry);
+        }
+
+        let path = path().ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Unable to find the home directory"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(&path)?;
+
+        // Another run could choose the same number between reading the journal and writing to it, so hold the lock
+        // until this run's first change has been written.
+        file.lock()?;
+        let run = load()?.iter().map(|entry| entry.run).max().map_or(1, |last| last + 1);
+        let entry = self.entry(run, repo, change);
+        let written = write_entry(&mut file, &entry);
+        file.unlock()?;
+        written?;
+
+        info!("Recording changes in {} as run {}", path.display(), run);
+        self.open = Some((file, run));
+        Ok(())
+    }
+
+    /// Creates the entry for `change` to `repo`, made by the run `run`.
+    fn entry(&self, run: u64, repo: &Repo, change: Change) -> Entry {
+        Entry {
+            run,
+            time: Utc::now(),
+            command: self.command.clone(),
+            undoes: self.undoes,
+            repo: repo.to_string(),
+            change,
+        }
+    }
+}
+
+/// Appends `entry` to the journal `file`.
+fn write_entry(file: &mut File, entry: &Entry) -> io::Result<()> {
+    // Each entry is written in one go, so that the journal is never left with half a line in it.
+    let mut line = serde_json::to_vec(entry).map_err(io::Error::other)?;
+    line.push(b'\n');
+    file.write_all(&line[..])
+}
+
+/// Reads every entry in the journal, oldest first. Lines that can't be read are skipped.
+pub fn load() -> io::Result<Vec<Entry>> {
+    let path = match path() {
+        Some(path) => path,
+        None => return Ok(Vec::new()),
+    };
+
+    let file = match File::open(&path) {
+        Ok(file) => file,
+        Err(ref err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(err),
+    };
+
+    let mut entries = Vec::new();
+    for (index, line) in BufReader::new(file).lines().enumerate() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        match serde_json::from_str(&line[..]) {
+            Ok(entry) => entries.push(entry),
+            Err(err) => warn!("Skipping line {} of {}: {}", index + 1, path.display(), err),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Reads every run in the journal, oldest first.
+pub fn runs() -> io::Result<Vec<Run>> {
+    let mut runs: Vec<Run> = Vec::new();
+
+    for entry in load()? {
+        // Runs that happened at the same time can be interleaved, so the run an entry belongs to isn't always the last
+        // one.
+        match runs.iter_mut().rev().find(|run| run.id == entry.run) {
+            Some(run) => run.entries.push(entry),
+            None => runs.push(Run {
+                id: entry.run,
+                entries: vec![entry],
+            }),
+        }
+    }
+
+    runs.sort_by_key(|run| run.id);
+    Ok(runs)
+}
+
+/// Gets the path to the journal, or `None` if it isn't possible to determine the user's home directory.
+pub fn path() -> Option<PathBuf> {
+    dirs::data_dir().map(|data_dir| data_dir.join("journal.jsonl"))
+}
@@ -0,0 +1,301 @@
+//
+//  journal/undo.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `undo` command, which reverts the changes made by a run recorded in the journal.
+//!
+//! Changes are undone in the opposite order to the one they were made in: a label that was created is deleted, and a
+//! label that was deleted is created again. Before each change is undone, the label is looked up, so that a label that
+//! has been changed since isn't overwritten, and running `undo` again for the same run only undoes what's left.
+
+use clap::ArgMatches;
+
+use super::{Change, Journal};
+use crate::client::{Client, Label};
+use crate::config::Config;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::util::exit::ExitCode;
+use crate::util::output::{self, Format};
+use crate::util::progress::Progress;
+use crate::util::repo::Repo;
+use crate::util::style::{self, Style};
+
+/// The results of undoing a run, as printed in the JSON format.
+#[derive(Serialize)]
+struct UndoOutput {
+    run: u64,
+    results: Vec<UndoResult>,
+}
+
+/// The result of undoing a single change.
+#[derive(Serialize)]
+struct UndoResult {
+    repo: String,
+    label: String,
+    undone: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// What happened when undoing a single change.
+enum Outcome {
+    /// The change was undone by making this change.
+    Undone(Change),
+    /// The change had already been undone, so nothing needed doing.
+    AlreadyUndone,
+}
+
+pub fn run(config: Config, client: &mut dyn Client, matches: &ArgMatches) -> Result<()> {
+    let format = config.output();
+    let style = Style::stdout(config.color());
+    let error_style = Style::stderr(config.color());
+    let quiet = config.quiet();
+
+    let id = matches
+        .value_of("run")
+        .and_then(|run| run.parse::<u64>().ok())
+        .expect("RUN is required and validated by clap");
+
+    let run = super::runs()?
+        .into_iter()
+        .find(|run| run.id == id)
+        .ok_or(ErrorKind::UnknownRun(id))?;
+
+    info!("Undoing {} change(s) made by run {} ({})", run.entries.len(), run.id, run.command());
+
+    let mut journal = Journal::start_undo(id);
+    let mut progress = Progress::new(&config, run.entries.len());
+    let mut results = Vec::new();
+    let mut rate_limited = None;
+
+    for entry in run.entries.iter().rev() {
+        let Change::Label {
+            ref name,
+            ref before,
+            ref after,
+        } = entry.change;
+
+        progress.set_message(entry.repo.clone());
+
+        let outcome = Repo::from_string(&entry.repo[..])
+            .map_err(|err| Error::from(err.to_string()))
+            .and_then(|repo| {
+                let outcome = undo_label(client, &repo, name, before.as_ref(), after.as_ref())?;
+                if let Some(rate_limit) = client.rate_limit(&repo.host[..]) {
+                    progress.set_rate_limit(rate_limit.remaining);
+                }
+
+                if let Outcome::Undone(ref change) = outcome {
+                    journal.record(&repo, change.clone());
+                }
+
+                Ok(outcome)
+            });
+
+        let error = match outcome {
+            Ok(Outcome::Undone(Change::Label { after: None, .. })) => {
+                let color = after.as_ref().map_or("", |label| &label.color[..]);
+                progress.suspend(|| match format {
+                    Format::Json => (),
+                    Format::Text if quiet => (),
+                    Format::Text => println!(
+                        "{} Deleted label {} from {}",
+                        style.marker(style::Change::Delete),
+                        style.label(&name[..], color),
+                        entry.repo
+                    ),
+                });
+
+                None
+            }
+            Ok(Outcome::Undone(Change::Label { after: Some(label), .. })) => {
+                progress.suspend(|| match format {
+                    Format::Json => (),
+                    Format::Text if quiet => (),
+                    Format::Text => println!(
+                        "{} Created label {} in {}",
+                        style.marker(style::Change::Create),
+                        style.label(&label.name[..], &label.color[..]),
+                        entry.repo
+                    ),
+                });
+
+                None
+            }
+            Ok(Outcome::AlreadyUndone) => {
+                info!("The change to label {} in {} has already been undone", name, entry.repo);
+                None
+            }
+            Err(err) => {
+                progress.suspend(|| match format {
+                    Format::Json => error!("Error: {}", err),
+                    Format::Text => eprintln!(
+                        "{} Couldn't undo the change to label {} in {}: {}",
+                        error_style.marker(style::Change::Fail),
+                        name,
+                        entry.repo,
+                        err
+                    ),
+                });
+
+                // Once the rate limit has been exhausted, every other request would fail too.
+                if err.exit_code() == ExitCode::RateLimited {
+                    rate_limited = Some(err);
+                    break;
+                }
+
+                Some(err.to_string())
+            }
+        };
+
+        progress.inc();
+
+        results.push(UndoResult {
+            repo: entry.repo.clone(),
+            label: name.clone(),
+            undone: error.is_none(),
+            error,
+        });
+    }
+
+    progress.finish();
+
+    let total = run.entries.len();
+    let failed = results.iter().filter(|result| !result.undone).count();
+
+    if format.is_json() {
+        output::print_json(&UndoOutput { run: id, results });
+    }
+
+    match rate_limited {
+        Some(err) => Err(err),
+        None if failed > 0 => Err(ErrorKind::PartialFailure {
+            verb: "undo",
+            failed,
+            total,
+            noun: "changes",
+        }.into()),
+        None => Ok(()),
+    }
+}
+
+/// Undoes a change to the label `name` in `repo`, which was `before` before the change and `after` after it.
+fn undo_label(
+    client: &mut dyn Client,
+    repo: &Repo,
+    name: &str,
+    before: Option<&Label>,
+    after: Option<&Label>,
+) -> Result<Outcome> {
+    let current = client.find_label(repo, name)?;
+
+    match (before, after, current) {
+        // The label was created, so delete it, as long as nobody has edited it since.
+        (None, Some(_), None) => Ok(Outcome::AlreadyUndone),
+        (None, Some(after), Some(current)) => {
+            if current != *after {
+                return Err(ErrorKind::UndoConflict("The label has been edited since it was created").into());
+            }
+
+            client.delete_label(repo, &current.name[..])?;
+            Ok(Outcome::Undone(Change::Label {
+                name: current.name.clone(),
+                before: Some(current),
+                after: None,
+            }))
+        }
+        // The label was deleted, so create it again, unless it already has been.
+        (Some(before), None, Some(current)) => {
+            if current == *before {
+                Ok(Outcome::AlreadyUndone)
+            } else {
+                Err(ErrorKind::UndoConflict("A different label with the same name has been created since").into())
+            }
+        }
+        (Some(before), None, None) => {
+            let created = client.create_label(repo, before)?;
+            Ok(Outcome::Undone(Change::Label {
+                name: created.name.clone(),
+                before: None,
+                after: Some(created),
+            }))
+        }
+        (Some(_), Some(_), _) => Err(ErrorKind::UndoConflict("Edits to labels can't be undone").into()),
+        (None, None, _) => Ok(Outcome::AlreadyUndone),
+    }
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "undo"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Revert the changes made by a run recorded in the journal"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            // <RUN>
+            Arg::with_name("run")
+                .value_name("RUN")
+                .help(
+                    "The number of the run to undo, as shown by `ghtool history`. Labels that were created are \
+                     deleted, which also removes them from every issue and pull request that has them, and labels \
+                     that were deleted are created again. Labels that have been edited since are left alone.",
+                )
+                .validator(validate_run)
+                .required(true)
+                .index(1),
+        ]
+    }
+
+    /// Checks that `value` is the number of a run.
+    pub fn validate_run(value: String) -> Result<(), String> {
+        match value.parse::<u64>() {
+            Ok(run) if run > 0 => Ok(()),
+            _ => Err("must be the number of a run, as shown by `ghtool history`".to_owned()),
+        }
+    }
+}
@@ -20,47 +20,104 @@
 
 use clap::ArgMatches;
 
-use config::Config as ParentConfig;
-use util::error::ArgError;
-use util::repo::Repo;
+use std::path::PathBuf;
+
+use crate::config::Config as ParentConfig;
+use crate::util::error::ArgError;
+use crate::util::repo::Repo;
+use crate::util::selector::Selector;
 
 /// Configuration for the `label copy` command.
 pub struct Config {
     pub parent_config: ParentConfig,
-    /// The repository to copy labels from.
-    pub from_repo: Repo,
-    /// The repository to copy labels to.
-    pub to_repo: Repo,
+    /// Where the labels are copied from and to.
+    pub source: Source,
+}
+
+/// Where the labels are copied from and to.
+pub enum Source {
+    /// Copy the labels from `from` to each of the repositories selected by `to_selectors`. If `label_names` isn't
+    /// empty, only the labels with those names are copied.
+    Repos {
+        from: Origin,
+        to_selectors: Vec<Selector>,
+        label_names: Vec<String>,
+    },
+    /// Carry on copying the labels that were left when `label copy` last stopped because the rate limit was exhausted.
+    Resume,
 }
 
-impl<'a> Config {
+/// Where the labels being copied come from.
+pub enum Origin {
+    /// The labels in a repository.
+    Repo(Repo),
+    /// The labels listed in the label manifest at this path.
+    Manifest(PathBuf),
+}
+
+impl Config {
     /// Attempts to create a `Config` by parsing command-line argument matches.
     pub fn from_matches(
         parent_config: ParentConfig,
-        matches: &'a ArgMatches,
-    ) -> Result<Config, ArgError<'a>> {
-        let from_string = matches
-            .value_of("from")
-            .ok_or(ArgError::NoValue { arg: "from" })?;
+        matches: &ArgMatches,
+    ) -> Result<Config, ArgError> {
+        if matches.is_present("resume") {
+            return Ok(Config {
+                parent_config,
+                source: Source::Resume,
+            });
+        }
+
+        let default_owner = parent_config.defaults().org.as_ref().map(|org| &org[..]);
 
-        let to_string = matches
-            .value_of("to")
-            .ok_or(ArgError::NoValue { arg: "to" })?;
+        // --from and --manifest conflict, and defaults.label_manifest is only used when neither is given.
+        let from = match (matches.value_of("from"), matches.value_of_os("manifest")) {
+            (Some(from_string), _) => Origin::Repo(
+                Repo::from_string_with_default_owner(from_string, default_owner).map_err(|err| {
+                    ArgError::InvalidRepo {
+                        arg: "from",
+                        value: from_string.to_owned(),
+                        error: err,
+                    }
+                })?,
+            ),
+            (None, Some(path)) => Origin::Manifest(PathBuf::from(path)),
+            (None, None) => Origin::Manifest(parent_config
+                .defaults()
+                .label_manifest
+                .clone()
+                .ok_or(ArgError::NoValue { arg: "from" })?),
+        };
 
-        let from_repo = Repo::from_string(&from_string[..]).ok_or(ArgError::InvalidValue {
-            arg: "from",
-            value: from_string,
-        })?;
+        let to_selectors = match matches.values_of("to") {
+            Some(values) => values
+                .map(|value| {
+                    Selector::from_string(value, default_owner, parent_config.groups()).map_err(|err| {
+                        ArgError::InvalidSelector {
+                            arg: "to",
+                            value: value.to_owned(),
+                            error: err,
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![Selector::Repo(parent_config
+                .default_repo()
+                .ok_or(ArgError::NoValue { arg: "to" })?)],
+        };
 
-        let to_repo = Repo::from_string(&to_string[..]).ok_or(ArgError::InvalidValue {
-            arg: "to",
-            value: to_string,
-        })?;
+        let label_names = matches
+            .values_of("label")
+            .map(|values| values.map(str::to_owned).collect())
+            .unwrap_or_default();
 
         Ok(Config {
             parent_config,
-            from_repo,
-            to_repo,
+            source: Source::Repos {
+                from,
+                to_selectors,
+                label_names,
+            },
         })
     }
 }
@@ -19,58 +19,365 @@
 //
 
 pub mod config;
-pub mod error;
-
-use self::config::Config;
-use self::error::CopyError;
-
-use hubcaps::labels::LabelOptions;
-use hubcaps::{Credentials, Github};
-use tokio_core::reactor::Core;
-
-pub fn run(config: Config) -> Result<(), CopyError> {
-    info!(
-        "Copying labels from {from} to {to}",
-        from = config.from_repo,
-        to = config.to_repo
-    );
-
-    let mut core = Core::new().map_err(|err| CopyError::IoError(err))?;
-
-    let github = Github::new(
-        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
-        Some(Credentials::Token(
-            config.parent_config.access_token().clone(),
-        )),
-        &core.handle(),
-    );
-
-    let (from_repo, to_repo) = (config.from_repo, config.to_repo);
-
-    core.run(
-        github
-            .repo(from_repo.user, from_repo.repo.clone())
-            .labels()
-            .list(),
-    )?
-        .iter()
-        .map(|from_label| {
-            info!("Found label \"{}\"", from_label.name);
-
-            github
-                .repo(to_repo.user.clone(), to_repo.repo.clone())
-                .labels()
-                .create(&LabelOptions::new(
-                    from_label.name.clone(),
-                    from_label.color.clone(),
-                ))
+pub mod resume;
+
+use self::config::{Config, Origin, Source};
+use self::resume::{Pending, Resume};
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::client::{Client, Label};
+use crate::completions::cache;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::journal::{self, Journal};
+use crate::label::manifest;
+use crate::util::exit::ExitCode;
+use crate::util::jobs;
+use crate::util::output::{self, Format};
+use crate::util::progress::Progress;
+use crate::util::repo::Repo;
+use crate::util::selector;
+use crate::util::style::{Change, Style};
+
+/// The results of copying labels, as printed in the JSON format.
+#[derive(Serialize)]
+struct CopyOutput {
+    from: String,
+    results: Vec<CopyResult>,
+    /// The number of labels that are still to be copied because the rate limit was exhausted.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pending: Option<usize>,
+}
+
+/// The result of copying a single label to a single repository.
+#[derive(Serialize)]
+struct CopyResult {
+    repo: String,
+    label: String,
+    copied: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// What happened when copying a single label to a single repository.
+enum Outcome {
+    /// The label was copied, and this is the label as GitHub created it.
+    Copied(Label),
+    Failed(Error),
+    /// The label wasn't copied because the rate limit had already been exhausted.
+    Skipped,
+}
+
+pub fn run(config: Config, client: &mut dyn Client) -> Result<()> {
+    let format = config.parent_config.output();
+    let style = Style::stdout(config.parent_config.color());
+    let error_style = Style::stderr(config.parent_config.color());
+    let quiet = config.parent_config.quiet();
+
+    let resuming = match config.source {
+        Source::Resume => true,
+        Source::Repos { .. } => false,
+    };
+
+    // The labels to copy to each repository.
+    let (from, to, work) = match config.source {
+        Source::Repos {
+            from,
+            to_selectors,
+            label_names,
+        } => {
+            let to_repos = selector::expand_all(&to_selectors[..], client)?;
+            let (from, mut from_labels) = match from {
+                Origin::Repo(from_repo) => {
+                    let labels = client.labels(&from_repo)?;
+                    cache::update(|cache| {
+                        cache.set_labels(&from_repo, labels.iter().map(|label| label.name.clone()))
+                    });
+                    (from_repo.to_string(), labels)
+                }
+                Origin::Manifest(path) => {
+                    let labels = manifest::load(&path)?;
+                    (path.display().to_string(), labels)
+                }
+            };
+
+            for label in &from_labels {
+                info!("Found label \"{}\"", label.name);
+            }
+
+            cache::update(|cache| cache.add_repos(&to_repos));
+
+            // Like GitHub, compare label names case-insensitively.
+            if !label_names.is_empty() {
+                let wanted = label_names.iter().map(|name| name.to_lowercase()).collect::<Vec<_>>();
+
+                if let Some(missing) = label_names
+                    .iter()
+                    .find(|name| !from_labels.iter().any(|label| label.name.to_lowercase() == name.to_lowercase()))
+                {
+                    return Err(ErrorKind::LabelNotFound {
+                        repo: from.clone(),
+                        name: missing.clone(),
+                    }.into());
+                }
+
+                from_labels.retain(|label| wanted.contains(&label.name.to_lowercase()));
+            }
+
+            let to = to_repos.iter().map(Repo::to_string).collect::<Vec<_>>();
+            let work = to_repos
+                .into_iter()
+                .map(|to_repo| (to_repo, from_labels.clone()))
+                .collect::<Vec<_>>();
+
+            (from, to, work)
+        }
+        Source::Resume => {
+            let resume = Resume::load().ok_or(ErrorKind::NothingToResume)?;
+            info!("Resuming copying {} labels from {}", resume.label_count(), resume.from);
+
+            let work = resume
+                .pending
+                .into_iter()
+                .filter_map(|pending| match Repo::from_string(&pending.repo[..]) {
+                    Ok(repo) => Some((repo, pending.labels)),
+                    Err(err) => {
+                        warn!("Skipping invalid repository {}: {}", pending.repo, err);
+                        None
+                    }
+                })
+                .collect::<Vec<_>>();
+
+            (resume.from, resume.to, work)
+        }
+    };
+
+    // Only mention which repository each label was copied to when there's more than one, so that the output for a
+    // single repository stays the same as it's always been.
+    let show_repo = work.len() > 1;
+
+    let repos = work.iter().map(|(repo, _)| repo.clone()).collect::<Vec<_>>();
+    let items = work
+        .into_iter()
+        .enumerate()
+        .flat_map(|(repo_index, (repo, labels))| {
+            labels
+                .into_iter()
+                .map(move |label| (repo_index, repo.clone(), label))
         })
-        .for_each(|to_create| match core.run(to_create) {
-            Ok(label) => println!("Copied label \"{}\"", label.name),
-            Err(err) => error!("Error: {}", err),
+        .collect::<Vec<_>>();
+
+    let mut results = Vec::new();
+    let mut progress = Progress::new(&config.parent_config, items.len());
+
+    // The names of the labels copied to each repository, for the completions cache.
+    let mut copied = vec![Vec::new(); repos.len()];
+
+    // Once the rate limit has been exhausted, every other request would fail too, so stop copying altogether, and
+    // remember which labels were still to be copied so that copying can be resumed.
+    let stop = AtomicBool::new(false);
+    let mut rate_limited = None;
+    let mut pending: Vec<(usize, Label)> = Vec::new();
+    let mut current_repo = None;
+
+    // Each label is recorded in the journal as soon as it's been copied, so that `undo` can delete it again.
+    let mut journal = Journal::start("label copy");
+
+    let work = move |client: &mut dyn Client, (repo_index, to_repo, from_label): (usize, Repo, Label)| {
+        if stop.load(Ordering::SeqCst) {
+            return (repo_index, from_label, Outcome::Skipped, None);
+        }
+
+        let outcome = match client.create_label(&to_repo, &from_label) {
+            Ok(label) => Outcome::Copied(label),
+            Err(err) => {
+                if err.exit_code() == ExitCode::RateLimited {
+                    stop.store(true, Ordering::SeqCst);
+                }
+
+                Outcome::Failed(err)
+            }
+        };
+
+        let remaining = client.rate_limit(&to_repo.host[..]).map(|rate_limit| rate_limit.remaining);
+
+        (repo_index, from_label, outcome, remaining)
+    };
+
+    jobs::run(config.parent_config.jobs(), client, items, work, |(repo_index, from_label, outcome, remaining)| {
+        let to_repo = &repos[repo_index];
+
+        if current_repo != Some(repo_index) {
+            info!("Copying labels from {from} to {to}", from = from, to = to_repo);
+            progress.set_message(to_repo.to_string());
+            current_repo = Some(repo_index);
+        }
+
+        if let Some(remaining) = remaining {
+            progress.set_rate_limit(remaining);
+        }
+
+        let error = match outcome {
+            Outcome::Copied(label) => {
+                let name = style.label(&label.name[..], &label.color[..]);
+                progress.suspend(|| match format {
+                    Format::Json => (),
+                    Format::Text if quiet => (),
+                    Format::Text if show_repo => {
+                        println!("{} Copied label {} to {}", style.marker(Change::Create), name, to_repo)
+                    }
+                    Format::Text => println!("{} Copied label {}", style.marker(Change::Create), name),
+                });
+
+                copied[repo_index].push(label.name.clone());
+                journal.record(
+                    to_repo,
+                    journal::Change::Label {
+                        name: label.name.clone(),
+                        before: None,
+                        after: Some(label),
+                    },
+                );
+                None
+            }
+            Outcome::Failed(err) => {
+                let rate_limit_exhausted = err.exit_code() == ExitCode::RateLimited;
+
+                // Requests that were already on their way when the rate limit ran out fail in the same way, so only
+                // the first of those failures is worth reporting.
+                if !rate_limit_exhausted || rate_limited.is_none() {
+                    progress.suspend(|| match format {
+                        Format::Json => error!("Error: {}", err),
+                        Format::Text => eprintln!(
+                            "{} Couldn't copy label {} to {}: {}",
+                            error_style.marker(Change::Fail),
+                            error_style.label(&from_label.name[..], &from_label.color[..]),
+                            to_repo,
+                            err
+                        ),
+                    });
+                }
+
+                if rate_limit_exhausted {
+                    if rate_limited.is_none() {
+                        rate_limited = Some(err);
+                    }
+
+                    pending.push((repo_index, from_label));
+                    return;
+                }
+
+                Some(err.to_string())
+            }
+            Outcome::Skipped => {
+                pending.push((repo_index, from_label));
+                return;
+            }
+        };
+
+        progress.inc();
+
+        results.push(CopyResult {
+            repo: to_repo.to_string(),
+            label: from_label.name.clone(),
+            copied: error.is_none(),
+            error,
         });
+    })?;
 
-    Ok(())
+    progress.finish();
+
+    cache::update(|cache| {
+        for (repo, names) in repos.iter().zip(copied) {
+            cache.add_labels(repo, names);
+        }
+    });
+
+    // Every label that wasn't copied because the rate limit was exhausted is still to be copied, grouped by the
+    // repository it's to be copied to.
+    let resume = rate_limited.as_ref().map(|_| {
+        let mut grouped: Vec<Pending> = Vec::new();
+
+        for (repo_index, label) in pending {
+            let repo = repos[repo_index].to_string();
+            if let Some(last) = grouped.last_mut() {
+                if last.repo == repo {
+                    last.labels.push(label);
+                    continue;
+                }
+            }
+
+            grouped.push(Pending {
+                repo,
+                labels: vec![label],
+            });
+        }
+
+        Resume {
+            from: from.clone(),
+            to,
+            pending: grouped,
+        }
+    });
+
+    if let Some(ref resume) = resume {
+        // Labels left over from a different copy haven't been copied yet, so they're kept rather than replaced.
+        match Resume::load() {
+            Some(ref saved) if !saved.is_same_copy(resume) => {
+                if format.is_json() {
+                    warn!("Not replacing the labels still to be copied from {}", saved.from);
+                } else {
+                    eprintln!(
+                        "{} {} labels are still to be copied, but {} labels from {} are already waiting to be copied. \
+                         Run `ghtool label copy --resume` to copy those first, then run this copy again.",
+                        error_style.hint("note:"),
+                        resume.label_count(),
+                        saved.label_count(),
+                        saved.from
+                    );
+                }
+            }
+            _ => match resume.save() {
+                Ok(path) => {
+                    info!("Saved the labels that are still to be copied to {}", path.display());
+                    if !format.is_json() {
+                        eprintln!(
+                            "{} {} labels are still to be copied. Run `ghtool label copy --resume` once the rate limit \
+                             resets to copy them.",
+                            error_style.hint("note:"),
+                            resume.label_count()
+                        );
+                    }
+                }
+                Err(err) => warn!("Unable to save the labels that are still to be copied: {}", err),
+            },
+        }
+    } else if resuming {
+        Resume::clear();
+    }
+
+    let total = results.len();
+    let failed = results.iter().filter(|result| !result.copied).count();
+
+    // In the JSON format, the results are only printed once everything is done, as a single document.
+    if format.is_json() {
+        output::print_json(&CopyOutput {
+            from,
+            results,
+            pending: resume.as_ref().map(Resume::label_count),
+        });
+    }
+
+    match rate_limited {
+        Some(err) => Err(err),
+        None if failed > 0 => Err(ErrorKind::PartialFailure {
+            verb: "copy",
+            failed,
+            total,
+            noun: "labels",
+        }.into()),
+        None => Ok(()),
+    }
 }
 
 /// Details about this command.
@@ -114,17 +421,49 @@ pub mod details {
                 .short("f")
                 .long("from")
                 .value_name("FROM")
-                .help("The repository to copy from, in the format \"user/repository\"")
+                .help(
+                    "The repository to copy from, in the format \"user/repository\" or as a URL. If neither this nor \
+                     --manifest is given, the labels are copied from the manifest named by defaults.label_manifest \
+                     in the configuration files.",
+                )
+                .takes_value(true),
+            // --manifest
+            Arg::with_name("manifest")
+                .long("manifest")
+                .value_name("PATH")
+                .help("A label manifest to copy the labels listed in, rather than copying from a repository")
                 .takes_value(true)
-                .required(true),
+                .conflicts_with("from"),
             // --to
             Arg::with_name("to")
                 .short("t")
                 .long("to")
                 .value_name("TO")
-                .help("The repository to copy to, in the format \"user/repository\"")
+                .help(
+                    "The repositories to copy to, in the format \"user/repository\" or as URLs, or selectors for \
+                     sets of repositories: \"owner/*\", \"@topic:owner/topic\", \"@team:org/team\", \
+                     \"@file:path\" or \":group\". Defaults to the value of defaults.repo in the configuration \
+                     files or, failing that, the GitHub repository that the current git checkout was cloned from.",
+                )
+                .takes_value(true)
+                .multiple(true),
+            // --label
+            Arg::with_name("label")
+                .short("l")
+                .long("label")
+                .value_name("LABEL")
+                .help("Only copy the label with this name. Can be given more than once to copy several labels.")
                 .takes_value(true)
-                .required(true),
+                .multiple(true)
+                .number_of_values(1),
+            // --resume
+            Arg::with_name("resume")
+                .long("resume")
+                .help(
+                    "Carry on copying the labels that were still to be copied when the last copy stopped because the \
+                     GitHub API rate limit was exhausted",
+                )
+                .conflicts_with_all(&["from", "manifest", "to", "label"]),
             // --clear
 //            Arg::with_name("clear")
 //                .short("c")
@@ -0,0 +1,116 @@
+//
+//  label/copy/resume.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The labels that were still to be copied when `label copy` stopped because the rate limit was exhausted, so that
+//! `label copy --resume` can carry on from where it stopped.
+//!
+//! Only one copy can be resumed at a time, so the labels left over from one copy are never replaced by those left over
+//! from a different one: they're kept until they've been copied, so that nothing is silently forgotten.
+
+use std::fs::{self, File};
+use std::io::{self, ErrorKind};
+use std::path::PathBuf;
+use std::process;
+
+use crate::client::Label;
+use crate::util::dirs;
+
+/// The labels that are still to be copied.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Resume {
+    /// The repository that the labels are being copied from.
+    pub from: String,
+    /// Every repository that the labels were being copied to, including those that they've all been copied to since.
+    pub to: Vec<String>,
+    /// The repositories that the labels are still to be copied to, in the order they were being copied in.
+    pub pending: Vec<Pending>,
+}
+
+/// The labels that are still to be copied to a single repository.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Pending {
+    pub repo: String,
+    pub labels: Vec<Label>,
+}
+
+impl Resume {
+    /// Loads the labels that are still to be copied, or returns `None` if there aren't any or they can't be read.
+    pub fn load() -> Option<Resume> {
+        let path = path()?;
+        let file = File::open(&path).ok()?;
+
+        match serde_json::from_reader(file) {
+            Ok(resume) => Some(resume),
+            Err(err) => {
+                warn!("Unable to read {}: {}", path.display(), err);
+                None
+            }
+        }
+    }
+
+    /// Saves the labels that are still to be copied, replacing any that were saved before.
+    ///
+    /// They're written to a temporary file that's then renamed into place, so that if ghtool is stopped while writing
+    /// them, the labels that were saved before are kept rather than being left half-overwritten.
+    pub fn save(&self) -> io::Result<PathBuf> {
+        let path = path().ok_or_else(|| io::Error::new(ErrorKind::NotFound, "Unable to find the home directory"))?;
+        let temp_path = path.with_extension(format!("{}.tmp", process::id()));
+
+        let result = path.parent()
+            .map_or(Ok(()), fs::create_dir_all)
+            .and_then(|_| File::create(&temp_path))
+            .and_then(|file| serde_json::to_writer_pretty(file, self).map_err(io::Error::other))
+            .and_then(|_| fs::rename(&temp_path, &path));
+
+        if result.is_err() {
+            let _ = fs::remove_file(&temp_path);
+        }
+
+        result.map(|_| path)
+    }
+
+    /// Returns whether `self` and `other` are left over from copying from the same repository to the same repositories.
+    pub fn is_same_copy(&self, other: &Resume) -> bool {
+        self.from == other.from && self.to == other.to
+    }
+
+    /// Returns the total number of labels that are still to be copied.
+    pub fn label_count(&self) -> usize {
+        self.pending.iter().map(|pending| pending.labels.len()).sum()
+    }
+
+    /// Deletes the saved labels, once they've all been copied.
+    pub fn clear() {
+        if let Some(path) = path() {
+            match fs::remove_file(&path) {
+                Err(ref err) if err.kind() != ErrorKind::NotFound => {
+                    warn!("Unable to delete {}: {}", path.display(), err)
+                }
+                _ => (),
+            }
+        }
+    }
+}
+
+/// Gets the path to the file that the labels are saved in. It's kept with the journal rather than in the cache, since
+/// losing it would lose track of the labels that haven't been copied.
+fn path() -> Option<PathBuf> {
+    dirs::data_dir().map(|data_dir| data_dir.join("copy-resume.json"))
+}
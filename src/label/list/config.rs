@@ -20,35 +20,48 @@
 
 use clap::ArgMatches;
 
-use config::Config as ParentConfig;
-use util::error::ArgError;
-use util::repo::Repo;
+use crate::config::Config as ParentConfig;
+use crate::util::error::ArgError;
+use crate::util::selector::Selector;
 
 /// Configuration for the `label list` command.
 pub struct Config {
     pub parent_config: ParentConfig,
-    /// The repository to list the labels from.
-    pub repo: Repo,
+    /// The repositories to list the labels from.
+    pub selectors: Vec<Selector>,
+    /// Whether to count the issues and pull requests that have each label.
+    pub usage: bool,
 }
 
-impl<'a> Config {
+impl Config {
     /// Attempts to create a `Config` by parsing command-line argument matches.
     pub fn from_matches(
         parent_config: ParentConfig,
-        matches: &'a ArgMatches,
-    ) -> Result<Config, ArgError<'a>> {
-        let repo_string = matches
-            .value_of("repo")
-            .ok_or(ArgError::NoValue { arg: "repo" })?;
+        matches: &ArgMatches,
+    ) -> Result<Config, ArgError> {
+        let default_owner = parent_config.defaults().org.as_ref().map(|org| &org[..]);
 
-        let repo = Repo::from_string(&repo_string[..]).ok_or(ArgError::InvalidValue {
-            arg: "repo",
-            value: repo_string,
-        })?;
+        let selectors = match matches.values_of("repo") {
+            Some(values) => values
+                .map(|value| {
+                    Selector::from_string(value, default_owner, parent_config.groups()).map_err(|err| {
+                        ArgError::InvalidSelector {
+                            arg: "repo",
+                            value: value.to_owned(),
+                            error: err,
+                        }
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?,
+            None => vec![Selector::Repo(parent_config
+                .default_repo()
+                .ok_or(ArgError::NoValue { arg: "repo" })?)],
+        };
 
         Ok(Config {
             parent_config,
-            repo,
+            selectors,
+            usage: matches.is_present("usage"),
         })
     }
 }
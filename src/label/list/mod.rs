@@ -19,36 +19,229 @@
 //
 
 pub mod config;
-pub mod error;
-
-use self::error::ListError;
-use futures::Stream;
-use hubcaps::{Credentials, Github};
-use tokio_core::reactor::Core;
-
-pub fn run(config: config::Config) -> Result<(), ListError> {
-    info!("Listing labels in {}...", config.repo);
-
-    let mut core = Core::new().map_err(|err| ListError::IoError(err))?;
-
-    let github = Github::new(
-        concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION")),
-        Some(Credentials::Token(
-            config.parent_config.access_token().clone(),
-        )),
-        &core.handle(),
-    );
-
-    core.run(
-        github
-            .repo(config.repo.user, config.repo.repo)
-            .labels()
-            .iter()
-            .for_each(|label| {
-                println!("{}", label.name);
-                Ok(())
-            }),
-    ).map_err(|err| ListError::HubcapsError(err))?;
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::client::Client;
+use crate::completions::cache;
+use crate::errors::{Error, ErrorKind, Result};
+use crate::util::exit::ExitCode;
+use crate::util::jobs;
+use crate::util::output::{self, Format};
+use crate::util::progress::Progress;
+use crate::util::repo::Repo;
+use crate::util::selector;
+use crate::util::style::{self, Style, Table};
+
+/// A label, as printed in the JSON format.
+#[derive(Serialize)]
+struct LabelOutput<'l> {
+    repo: String,
+    name: &'l str,
+    color: &'l str,
+    /// The label's description, which is left out if it doesn't have one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'l str>,
+    /// The number of issues and pull requests with the label, with `--usage`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    issues: Option<u64>,
+}
+
+/// A repository whose labels couldn't be listed, as printed in the JSON format in place of its labels.
+#[derive(Serialize)]
+struct ErrorOutput {
+    repo: String,
+    error: String,
+}
+
+pub fn run(config: config::Config, client: &mut dyn Client) -> Result<()> {
+    let format = config.parent_config.output();
+    let style = Style::stdout(config.parent_config.color());
+    let error_style = Style::stderr(config.parent_config.color());
+    let repos = selector::expand_all(&config.selectors[..], client)?;
+
+    // Only prefix each label with its repository when there's more than one, so that the output for a single
+    // repository stays easy to consume from scripts.
+    let show_repo = repos.len() > 1;
+
+    // On a terminal, the labels are printed as a table once they've all been found, with each label drawn in its
+    // colour. Otherwise, each repository's labels are printed as soon as they've been found, in the same way as always.
+    let mut table = if style.is_terminal() && format == Format::Text {
+        Some(Table::new())
+    } else {
+        None
+    };
+
+    let mut progress = Progress::new(&config.parent_config, repos.len());
+
+    // Clients that can list the labels of many repositories at once are given them in batches, and each batch is one
+    // piece of work.
+    let batches = repos
+        .chunks(client.batch_size().max(1))
+        .map(<[Repo]>::to_vec)
+        .collect::<Vec<_>>();
+
+    // A repository that can't be listed doesn't stop the others from being listed, unless the rate limit has been
+    // exhausted, in which case every other request would fail too.
+    let stop = AtomicBool::new(false);
+    // The error that stopped the command, if the rate limit was exhausted or there's only one repository to list.
+    let mut fatal: Option<Error> = None;
+    let mut failed = 0;
+    let total = repos.len();
+    let count_issues = config.usage;
+
+    // The names of the labels in each repository that was listed, to be remembered for completions.
+    let mut listed = Vec::new();
+
+    let work = move |client: &mut dyn Client, batch: Vec<Repo>| {
+        if stop.load(Ordering::SeqCst) {
+            return (batch, None);
+        }
+
+        match batch.len() {
+            1 => info!("Listing labels in {}...", batch[0]),
+            count => info!("Listing labels in {} repositories, starting with {}...", count, batch[0]),
+        }
+
+        let results = client.bulk_labels(&batch[..], count_issues);
+        let exhausted = |err: &Error| err.exit_code() == ExitCode::RateLimited;
+        let rate_limited = match results {
+            Ok(ref results) => results.iter().any(|result| result.as_ref().err().is_some_and(exhausted)),
+            Err(ref err) => exhausted(err),
+        };
+
+        if rate_limited {
+            stop.store(true, Ordering::SeqCst);
+        }
+
+        (batch, Some(results))
+    };
+
+    jobs::run(config.parent_config.jobs(), client, batches, work, |(batch, results)| {
+        // The repositories are passed on in the order they were given in, so every repository after the rate limit
+        // was exhausted is left out, whether or not it was listed before that was noticed.
+        if fatal.is_some() {
+            return;
+        }
+
+        // If the whole batch failed, then so did each repository in it.
+        let results = match results {
+            Some(Ok(results)) => results,
+            Some(Err(err)) => {
+                let message = err.to_string();
+                let mut results = vec![Err(err)];
+                results.extend((1..batch.len()).map(|_| Err(Error::from(message.clone()))));
+                results
+            }
+            None => return,
+        };
+
+        for (repo, labels) in batch.into_iter().zip(results) {
+            let labels = match labels {
+                Ok(labels) => labels,
+                // A single repository's error is reported just like any other command's.
+                Err(err) if err.exit_code() == ExitCode::RateLimited || total == 1 => {
+                    fatal = Some(err);
+                    return;
+                }
+                Err(err) => {
+                    progress.suspend(|| match format {
+                        Format::Json => {
+                            error!("Error: {}", err);
+                            output::print_json(&ErrorOutput {
+                                repo: repo.to_string(),
+                                error: err.to_string(),
+                            });
+                        }
+                        Format::Text => eprintln!(
+                            "{} Couldn't list the labels in {}: {}",
+                            error_style.marker(style::Change::Fail),
+                            repo,
+                            err
+                        ),
+                    });
+
+                    failed += 1;
+                    progress.inc();
+                    continue;
+                }
+            };
+
+            progress.set_message(repo.to_string());
+
+            for usage in &labels {
+                let label = &usage.label;
+
+                // In the JSON format, each label is printed on its own line.
+                match (format, table.as_mut()) {
+                    (Format::Json, _) => output::print_json(&LabelOutput {
+                        repo: repo.to_string(),
+                        name: &label.name[..],
+                        color: &label.color[..],
+                        description: label.description.as_deref().filter(|description| !description.is_empty()),
+                        issues: usage.issues,
+                    }),
+                    (Format::Text, Some(table)) => {
+                        let mut row = Vec::new();
+                        if show_repo {
+                            row.push(style.dim(&repo.to_string()[..]));
+                        }
+
+                        row.push(style.label(&label.name[..], &label.color[..]));
+                        row.push(style.dim(&format!("#{}", label.color)[..]));
+                        if let Some(issues) = usage.issues {
+                            row.push(style.dim(&format!("{} issue(s)", issues)[..]));
+                        }
+
+                        table.push(row);
+                    }
+                    (Format::Text, None) => {
+                        let mut line = if show_repo {
+                            format!("{}: {}", repo, label.name)
+                        } else {
+                            label.name.clone()
+                        };
+
+                        // The count is separated by a tab, since label names can contain spaces.
+                        if let Some(issues) = usage.issues {
+                            line.push_str(&format!("\t{}", issues));
+                        }
+
+                        progress.suspend(|| println!("{}", line));
+                    }
+                }
+            }
+
+            listed.push((repo, labels.into_iter().map(|usage| usage.label.name).collect::<Vec<_>>()));
+            progress.inc();
+        }
+    })?;
+
+    progress.finish();
+
+    cache::update(|cache| {
+        for (repo, names) in listed {
+            cache.set_labels(&repo, names);
+        }
+    });
+
+    // The labels that were found are printed even if some repositories couldn't be listed.
+    if let Some(table) = table {
+        table.print();
+    }
+
+    if let Some(err) = fatal {
+        return Err(err);
+    }
+
+    if failed > 0 {
+        return Err(ErrorKind::PartialFailure {
+            verb: "list the labels in",
+            failed,
+            total,
+            noun: "repositories",
+        }.into());
+    }
 
     Ok(())
 }
@@ -92,9 +285,21 @@ pub mod details {
             Arg::with_name("repo")
                 .index(1)
                 .value_name("REPO")
-                .help("The repository, in the format \"user/repository\"")
+                .help(
+                    "The repositories, in the format \"user/repository\" or as URLs, or selectors for sets of \
+                     repositories: \"owner/*\", \"@topic:owner/topic\", \"@team:org/team\", \"@file:path\" or \
+                     \":group\". Defaults to the value of defaults.repo in the configuration files or, failing that, \
+                     the GitHub repository that the current git checkout was cloned from.",
+                )
                 .takes_value(true)
-                .required(true),
+                .multiple(true),
+            Arg::with_name("usage")
+                .long("usage")
+                .short("u")
+                .help(
+                    "Also print the number of issues and pull requests, open or closed, that have each label. Labels \
+                     that aren't used anywhere can be found this way before cleaning them up.",
+                ),
         ]
     }
 }
@@ -0,0 +1,119 @@
+//
+//  label/manifest.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 28/02/2018.
+//  Copyright © 2018 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Label manifests: TOML files that list the labels a repository should have, for `label copy --manifest` and the
+//! `defaults.label_manifest` configuration key.
+//!
+//! A manifest has a `[[labels]]` table for each label:
+//!
+//! ```toml
+//! [[labels]]
+//! name = "bug"
+//! color = "ee0701"
+//! description = "Something isn't working"
+//! ```
+
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+use crate::client::Label;
+use crate::errors::{ErrorKind, Result};
+
+#[derive(Deserialize)]
+struct Manifest {
+    #[serde(default)]
+    labels: Vec<Label>,
+}
+
+/// Reads the labels listed in the manifest at `path`.
+pub fn load(path: &Path) -> Result<Vec<Label>> {
+    let mut contents = String::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .map_err(|err| invalid(path, err.to_string()))?;
+
+    parse(&contents[..]).map_err(|reason| invalid(path, reason).into())
+}
+
+/// Parses the contents of a manifest, returning the reason it's invalid if it is.
+fn parse(contents: &str) -> ::std::result::Result<Vec<Label>, String> {
+    let manifest: Manifest = toml::from_str(contents).map_err(|err| err.to_string())?;
+
+    if let Some(label) = manifest.labels.iter().find(|label| !is_color(&label.color[..])) {
+        return Err(format!(
+            "the colour of \"{}\" should be six hexadecimal digits, e.g. \"ee0701\", not \"{}\"",
+            label.name, label.color
+        ));
+    }
+
+    Ok(manifest.labels)
+}
+
+fn is_color(color: &str) -> bool {
+    color.len() == 6 && color.chars().all(|c| c.is_ascii_hexdigit())
+}
+
+fn invalid(path: &Path, reason: String) -> ErrorKind {
+    ErrorKind::InvalidManifest {
+        path: path.to_owned(),
+        reason,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_labels() {
+        let labels = parse(
+            "[[labels]]\nname = \"bug\"\ncolor = \"ee0701\"\ndescription = \"Something isn't working\"\n\n\
+             [[labels]]\nname = \"wontfix\"\ncolor = \"FFFFFF\"\n",
+        ).unwrap();
+
+        assert_eq!(
+            labels,
+            vec![
+                Label {
+                    name: "bug".to_owned(),
+                    color: "ee0701".to_owned(),
+                    description: Some("Something isn't working".to_owned()),
+                },
+                Label {
+                    name: "wontfix".to_owned(),
+                    color: "FFFFFF".to_owned(),
+                    description: None,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_colors() {
+        let err = parse("[[labels]]\nname = \"bug\"\ncolor = \"#ee0701\"\n").unwrap_err();
+        assert!(err.contains("\"bug\""), "{}", err);
+    }
+
+    #[test]
+    fn rejects_labels_without_a_name() {
+        assert!(parse("[[labels]]\ncolor = \"ee0701\"\n").is_err());
+    }
+}
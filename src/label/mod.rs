@@ -19,29 +19,28 @@
 //
 
 pub mod copy;
-pub mod error;
 pub mod list;
+pub mod manifest;
 
 use clap::ArgMatches;
 
-use self::error::Error;
-use config::Config;
+use crate::client::Client;
+use crate::config::Config;
+use crate::errors::{ErrorKind, Result};
 
-pub fn run<'a>(parent_config: Config, matches: &'a ArgMatches) -> Result<(), Error<'a>> {
+pub fn run(parent_config: Config, client: &mut dyn Client, matches: &ArgMatches) -> Result<()> {
     match matches.subcommand() {
         ("list", Some(list_matches)) => {
-            let config = list::config::Config::from_matches(parent_config, &list_matches)
-                .map_err(|err| Error::ArgError(err))?;
-            list::run(config).map_err(|err| Error::ListError(err))
+            let config = list::config::Config::from_matches(parent_config, list_matches)?;
+            list::run(config, client)
         }
         ("copy", Some(copy_matches)) => {
-            let config = copy::config::Config::from_matches(parent_config, &copy_matches)
-                .map_err(|err| Error::ArgError(err))?;
-            copy::run(config).map_err(|err| Error::CopyError(err))
+            let config = copy::config::Config::from_matches(parent_config, copy_matches)?;
+            copy::run(config, client)
         }
         ("", None) => {
             let _ = details::app().print_help();
-            Err(Error::NoSubcommand)
+            Err(ErrorKind::NoSubcommand.into())
         }
         _ => unreachable!(),
     }
@@ -58,8 +57,7 @@ pub mod details {
             .author(author())
             .about(description())
             .args(&args()[..])
-            .subcommand(super::list::details::app())
-            .subcommand(super::copy::details::app())
+            .subcommands(subcommands())
     }
 
     /// This command's name.
@@ -86,4 +84,9 @@ pub mod details {
     fn description() -> &'static str {
         "Make modifications to the issue labels in a GitHub repository"
     }
+
+    /// This command's subcommands.
+    pub fn subcommands() -> Vec<App<'static, 'static>> {
+        vec![super::list::details::app(), super::copy::details::app()]
+    }
 }
@@ -18,27 +18,55 @@
 //  limitations under the License.
 //
 
-extern crate clap;
+#[macro_use]
 extern crate error_chain;
-extern crate futures;
-extern crate hubcaps;
+#[macro_use]
+extern crate lazy_static;
 #[macro_use]
 extern crate log;
-extern crate pretty_logger;
-extern crate regex;
 #[macro_use]
 extern crate serde_derive;
-extern crate tokio_core;
-extern crate toml;
 
+pub mod auth;
+pub mod cache;
+pub mod client;
+pub mod completions;
 pub mod config;
+pub mod errors;
+pub mod journal;
 pub mod label;
+pub mod man;
 pub mod util;
 
-use config::{Config, IntoConfig, StoredConfig};
+use clap::{ArgMatches, ErrorKind as ClapErrorKind};
+
+use crate::auth::Auth;
+use crate::client::GithubClient;
+use crate::config::{Config, ConfigError, IntoConfig, Locations, StoredConfig};
+use crate::util::exit::ExitCode;
+use crate::util::output::Format;
+use crate::util::repo::Repo;
+use crate::util::style::ColorChoice;
+
+use std::path::{Path, PathBuf};
+use std::process;
 
 fn main() {
-    let matches = details::app().get_matches();
+    process::exit(run().code());
+}
+
+/// Runs ghtool, returning the code that it should exit with.
+fn run() -> ExitCode {
+    let matches = match details::app().get_matches_safe() {
+        Ok(matches) => matches,
+        Err(ref err) if err.kind == ClapErrorKind::HelpDisplayed || err.kind == ClapErrorKind::VersionDisplayed => {
+            err.exit()
+        }
+        Err(err) => {
+            eprintln!("{}", err.message);
+            return ExitCode::Usage;
+        }
+    };
 
     // Set the level of verbosity based on the -v flag.
     match matches.occurrences_of("v") {
@@ -49,57 +77,199 @@ fn main() {
         _ => {
             let _ = pretty_logger::init_to_defaults();
             error!("Invalid verbosity level (maximum is -vvv)");
-            return;
+            return ExitCode::Usage;
         }
     }.expect("Could not initialise logging.");
 
     info!("Using verbosity level: {}", log::max_log_level());
 
-    if StoredConfig::file_exists() {
-        info!("--token argument not required: config file found at default location");
+    let output = Format::from_matches(&matches);
+    let color = ColorChoice::from_matches(&matches);
+
+    let locations = Locations::discover(matches.value_of_os("config").map(Path::new));
+    debug!("Configuration file locations: {:?}", locations);
+
+    // The config command manages the configuration file itself, completions and man pages have to work before ghtool
+    // has been set up at all, and clearing the cache and showing the journal don't need credentials, so these commands
+    // have to work without a valid configuration file.
+    match matches.subcommand() {
+        ("config", Some(config_matches)) => {
+            return match config::command::run(&locations, matches.value_of("token"), output, config_matches) {
+                Ok(()) => ExitCode::Success,
+                Err(err) => {
+                    err.report(output, color);
+                    err.exit_code()
+                }
+            };
+        }
+        ("cache", Some(cache_matches)) => {
+            return match cache::run(output, cache_matches) {
+                Ok(()) => ExitCode::Success,
+                Err(err) => {
+                    err.report(output, color);
+                    err.exit_code()
+                }
+            };
+        }
+        ("history", Some(history_matches)) => {
+            return match journal::history::run(output, color, history_matches) {
+                Ok(()) => ExitCode::Success,
+                Err(err) => {
+                    err.report(output, color);
+                    err.exit_code()
+                }
+            };
+        }
+        ("completions", Some(completions_matches)) => {
+            return match completions::run(details::app(), completions_matches) {
+                Ok(()) => ExitCode::Success,
+                Err(err) => {
+                    err.report(output, color);
+                    err.exit_code()
+                }
+            };
+        }
+        ("man", Some(man_matches)) => {
+            return match man::run(details::app(), man_matches) {
+                Ok(()) => ExitCode::Success,
+                Err(err) => {
+                    err.report(output, color);
+                    err.exit_code()
+                }
+            };
+        }
+        ("complete-args", Some(complete_matches)) => {
+            completions::complete::run(&locations, complete_matches);
+            return ExitCode::Success;
+        }
+        ("", None) => {
+            let _ = details::app().print_help();
+            return ExitCode::Usage;
+        }
+        _ => (),
     }
 
-    let config = match (matches.value_of("token"), StoredConfig::try_load()) {
-        (Some(token), Ok(_)) => {
-            info!("Overriding access token in configuration file with value from --token argument");
-            Config {
-                access_token: token.to_owned(),
-            }
+    if StoredConfig::file_exists(&locations) {
+        info!("--token argument not required: config file found");
+    }
+
+    let token_auth = matches.value_of("token").map(|token| Auth::Token(token.to_owned()));
+
+    let into_config = match (token_auth, StoredConfig::try_load(&locations)) {
+        (Some(auth), Ok(stored_config)) => {
+            info!("Overriding credentials in configuration file with access token from --token argument");
+            IntoConfig::from_stored(stored_config, Some(auth))
         }
-        (Some(token), Err(_)) => {
+        (Some(auth), Err(ConfigError::FileMissing)) => {
             info!("Using access token provided by --token argument");
-            Config {
-                access_token: token.to_owned(),
-            }
+            Ok(IntoConfig::default(auth))
+        }
+        (Some(auth), Err(err)) => {
+            warn!("Ignoring configuration files: {}", err);
+            info!("Using access token provided by --token argument");
+            Ok(IntoConfig::default(auth))
         }
-        (None, Ok(stored_config)) => IntoConfig::from(stored_config).build(),
+        (None, Ok(stored_config)) => IntoConfig::from_stored(stored_config, None),
         (None, Err(err)) => {
-            error!("Could not read configuration file: {}", err);
-            return;
+            let err = errors::Error::from(err);
+            err.report(output, color);
+            return err.exit_code();
+        }
+    };
+
+    let config: Config = match into_config {
+        Ok(into_config) => into_config
+            .output(output)
+            .color(color)
+            .quiet(matches.is_present("quiet"))
+            .jobs(matches.value_of("jobs").and_then(|jobs| jobs.parse().ok()).unwrap_or(1))
+            .ca_bundle(matches.value_of_os("cacert").map(PathBuf::from))
+            .use_cache(!matches.is_present("no-cache"))
+            .trusted_hosts(trusted_hosts(&locations, &matches))
+            .build(),
+        Err(err) => {
+            let err = errors::Error::from(err);
+            err.report(output, color);
+            return err.exit_code();
         }
     };
 
-    // Now go into the subcommand. Exit with an error if no subcommand was specified.
+    let mut client = match GithubClient::new(&config) {
+        Ok(client) => client,
+        Err(err) => {
+            err.report(output, color);
+            return err.exit_code();
+        }
+    };
+
+    // Now go into the subcommand.
     match matches.subcommand() {
-        ("label", Some(label_matches)) => match label::run(config, label_matches) {
-            Ok(()) => (),
+        ("label", Some(label_matches)) => match label::run(config, &mut client, label_matches) {
+            Ok(()) => ExitCode::Success,
             Err(err) => {
-                println!("{}", err);
+                err.report(output, color);
+                err.exit_code()
+            }
+        },
+        ("undo", Some(undo_matches)) => match journal::undo::run(config, &mut client, undo_matches) {
+            Ok(()) => ExitCode::Success,
+            Err(err) => {
+                err.report(output, color);
+                err.exit_code()
             }
         },
-        ("", None) => {
-            let _ = details::app().print_help();
-            return;
-        }
         _ => unreachable!(),
     }
 }
 
+/// Returns the hosts of the GitHub instances that credentials may be sent to, besides github.com: those named in the
+/// system-wide and user's configuration files, those of repositories given as arguments, and, for `undo`, those of
+/// repositories that the journal records changes to, which were trusted when the changes were made.
+fn trusted_hosts(locations: &Locations, matches: &ArgMatches) -> Vec<String> {
+    let mut hosts = config::trusted_hosts(locations);
+    hosts.extend(argument_hosts(matches));
+
+    if let ("undo", Some(_)) = matches.subcommand() {
+        let entries = journal::load().unwrap_or_default();
+        hosts.extend(
+            entries
+                .iter()
+                .filter_map(|entry| Repo::from_string(&entry.repo[..]).ok())
+                .map(|repo| repo.host),
+        );
+    }
+
+    hosts
+}
+
+/// Returns the hosts of the repositories given as arguments to the command in `matches` or any of its subcommands.
+fn argument_hosts(matches: &ArgMatches) -> Vec<String> {
+    // Selectors for groups and files don't name a host themselves.
+    let mut hosts = ["repo", "from", "to"]
+        .iter()
+        .filter_map(|name| matches.values_of(name))
+        .flatten()
+        .filter(|value| !value.starts_with(':') && !value.starts_with('@'))
+        .filter_map(|value| Repo::from_string(value).ok())
+        .map(|repo| repo.host)
+        .collect::<Vec<_>>();
+
+    if let (_, Some(subcommand_matches)) = matches.subcommand() {
+        hosts.extend(argument_hosts(subcommand_matches));
+    }
+
+    hosts
+}
+
 /// Details about this app.
 mod details {
     use clap::{App, Arg};
-    use config::StoredConfig;
-    use label;
+    use crate::cache;
+    use crate::completions;
+    use crate::config;
+    use crate::journal;
+    use crate::label;
+    use crate::man;
 
     /// This command's app definition.
     pub fn app() -> App<'static, 'static> {
@@ -108,7 +278,22 @@ mod details {
             .author(author())
             .about(description())
             .args(&args()[..])
-            .subcommand(label::details::app())
+            .subcommands(subcommands())
+            .subcommand(completions::complete::details::app())
+    }
+
+    /// This app's subcommands, apart from the hidden `complete-args`, which is only run by the completion scripts and
+    /// so has no man page.
+    pub fn subcommands() -> Vec<App<'static, 'static>> {
+        vec![
+            config::command::details::app(),
+            label::details::app(),
+            journal::history::details::app(),
+            journal::undo::details::app(),
+            cache::details::app(),
+            completions::details::app(),
+            man::details::app(),
+        ]
     }
 
     /// This app's name.
@@ -140,11 +325,71 @@ mod details {
                 .value_name("TOKEN")
                 .help(
                     "GitHub personal access token that provides access to the repositories specified by <FROM> and \
-                    <TO>. Overrides any existing value from ~/.config/ghtool/config.toml. Not required if a \
+                    <TO>. Overrides any existing value from the configuration files. Not required if a \
                     configuration file is found."
                 )
-                .takes_value(true)
-                .required(!StoredConfig::file_exists()),
+                .takes_value(true),
+            Arg::with_name("config")
+                .long("config")
+                .value_name("PATH")
+                .help(
+                    "Path to the user configuration file. Overrides the GHTOOL_CONFIG environment variable and the \
+                    default of $XDG_CONFIG_HOME/ghtool/config.toml."
+                )
+                .takes_value(true),
+            Arg::with_name("cacert")
+                .long("cacert")
+                .value_name("PATH")
+                .help(
+                    "Path to a file of PEM-encoded certificates to trust when connecting to GitHub, as well as the \
+                    system's, e.g. for a proxy that intercepts TLS. Overrides ca_bundle in the configuration files."
+                )
+                .takes_value(true),
+            Arg::with_name("output")
+                .long("output")
+                .short("o")
+                .value_name("FORMAT")
+                .help(
+                    "The format to print results in. With \"json\", results are printed to stdout as JSON, and \
+                    logs and errors are printed to stderr."
+                )
+                .possible_values(&["text", "json"])
+                .default_value("text")
+                .takes_value(true),
+            Arg::with_name("color")
+                .long("color")
+                .value_name("WHEN")
+                .help(
+                    "When to print text in colour. With \"auto\", colour is used when printing to a terminal, \
+                    unless the NO_COLOR environment variable is set."
+                )
+                .possible_values(&["auto", "always", "never"])
+                .default_value("auto")
+                .takes_value(true),
+            Arg::with_name("quiet")
+                .long("quiet")
+                .short("q")
+                .help("Only print results and errors, without progress bars or messages about what's been done"),
+            Arg::with_name("jobs")
+                .long("jobs")
+                .short("j")
+                .value_name("N")
+                .help(
+                    "The number of requests to send to GitHub at once when acting on many repositories or labels. \
+                    Results are printed in the same order whatever the number."
+                )
+                .validator(|value| match value.parse::<usize>() {
+                    Ok(jobs) if jobs > 0 => Ok(()),
+                    _ => Err("must be a whole number greater than 0".to_owned()),
+                })
+                .default_value("1")
+                .takes_value(true),
+            Arg::with_name("no-cache")
+                .long("no-cache")
+                .help(
+                    "Don't use or update the cache of responses from GitHub, so that every request is sent in full \
+                    and counts towards the rate limit"
+                ),
             Arg::with_name("v")
                 .short("v")
                 .multiple(true)
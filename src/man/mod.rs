@@ -0,0 +1,352 @@
+//
+//  man/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The `man` command, which renders man pages for ghtool and each of its commands.
+//!
+//! Each section of a page is rendered by clap from the command's `details` module, using a help template that picks
+//! out just that section, so the pages never disagree with `--help`.
+
+use clap::{App, AppSettings, ArgMatches};
+
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::Path;
+
+use crate::errors::Result;
+use crate::util::error::ArgError;
+
+/// The name of the binary, which is the first component of the name of every page.
+const BIN_NAME: &str = env!("CARGO_PKG_NAME");
+
+/// A man page for one command.
+struct Page {
+    /// The words that invoke the command, e.g. `["ghtool", "label", "copy"]`.
+    command: Vec<String>,
+    /// The command's app definition.
+    app: App<'static, 'static>,
+}
+
+impl Page {
+    /// The name of the page, e.g. "ghtool-label-copy".
+    fn name(&self) -> String {
+        self.command.join("-")
+    }
+
+    /// Renders the page as roff. `all_names` are the names of every page, which are listed under SEE ALSO.
+    fn render(&self, all_names: &[String]) -> String {
+        let name = self.name();
+        let mut roff = String::new();
+
+        roff.push_str(&format!(
+            ".TH \"{}\" 1 \"\" \"{} {}\" \"{} Manual\"\n",
+            name.to_uppercase(),
+            BIN_NAME,
+            env!("CARGO_PKG_VERSION"),
+            BIN_NAME
+        ));
+
+        roff.push_str(".SH NAME\n");
+        roff.push_str(&format!("{} \\- {}\n", escape(&name[..]), escape(self.section("{about}").trim())));
+
+        roff.push_str(".SH SYNOPSIS\n");
+        roff.push_str(&format!("\\fB{}\\fR\n", escape(self.section("{usage}").trim())));
+
+        for &(title, template) in &[
+            ("ARGUMENTS", "{positionals}"),
+            ("FLAGS", "{flags}"),
+            ("OPTIONS", "{options}"),
+            ("SUBCOMMANDS", "{subcommands}"),
+        ] {
+            let items = self.section(template);
+            if !items.trim().is_empty() {
+                roff.push_str(&format!(".SH {}\n", title));
+                roff.push_str(&items_to_roff(&items[..], template == "{subcommands}"));
+            }
+        }
+
+        let others = all_names
+            .iter()
+            .filter(|other| **other != name)
+            .map(|other| format!("\\fB{}\\fR(1)", escape(other)))
+            .collect::<Vec<_>>();
+
+        if !others.is_empty() {
+            roff.push_str(".SH SEE ALSO\n");
+            roff.push_str(&format!("{}\n", others.join(", ")));
+        }
+
+        roff
+    }
+
+    /// Renders one section of the command's help with the help template `template`.
+    fn section(&self, template: &'static str) -> String {
+        // With a terminal width of 0, clap doesn't wrap the help text itself, which leaves that to the man page viewer.
+        let app = self.app
+            .clone()
+            .bin_name(self.command.join(" "))
+            .template(template)
+            .setting(AppSettings::NextLineHelp)
+            .set_term_width(0);
+
+        let mut help = Vec::new();
+        let _ = app.write_help(&mut help);
+        String::from_utf8_lossy(&help).into_owned()
+    }
+}
+
+/// Renders the man pages. If `--dir` is given, every page is written to that directory; otherwise, the page named by
+/// the "page" argument is printed. `app` is the definition of the whole app.
+pub fn run(app: App<'static, 'static>, matches: &ArgMatches) -> Result<()> {
+    let pages = pages(app);
+    let names = pages.iter().map(Page::name).collect::<Vec<_>>();
+
+    if let Some(dir) = matches.value_of_os("dir").map(Path::new) {
+        fs::create_dir_all(dir)?;
+
+        for page in &pages {
+            let path = dir.join(format!("{}.1", page.name()));
+            info!("Writing {}", path.display());
+
+            File::create(&path).and_then(|mut file| file.write_all(page.render(&names[..]).as_bytes()))?;
+        }
+
+        return Ok(());
+    }
+
+    let page_name = matches.value_of("page").unwrap_or(BIN_NAME);
+    let page = pages
+        .iter()
+        .find(|page| page.name() == page_name)
+        .ok_or(ArgError::InvalidValue {
+            arg: "page",
+            value: page_name.to_owned(),
+        })?;
+
+    print!("{}", page.render(&names[..]));
+
+    Ok(())
+}
+
+/// Returns a page for ghtool itself, whose definition is `app`, and one for each of its commands and their
+/// subcommands. Hidden commands don't get a page.
+fn pages(app: App<'static, 'static>) -> Vec<Page> {
+    let mut pages = Vec::new();
+    push_pages(&mut pages, vec![BIN_NAME.to_owned()], app);
+    pages
+}
+
+/// Adds a page for the command invoked by the words in `command`, whose definition is `app`, to `pages`, followed by
+/// a page for each of its subcommands.
+fn push_pages(pages: &mut Vec<Page>, command: Vec<String>, app: App<'static, 'static>) {
+    pages.push(Page {
+        command: command.clone(),
+        app,
+    });
+
+    for subcommand in subcommands(&command[1..]) {
+        let mut subcommand_words = command.clone();
+        subcommand_words.push(subcommand.get_name().to_owned());
+        push_pages(pages, subcommand_words, subcommand);
+    }
+}
+
+/// Returns the definitions of the visible subcommands of the command invoked by `ghtool` followed by `words`, from the
+/// same `details` modules that the app is built from. clap doesn't give access to the subcommands of an `App`, so a
+/// command with subcommands has to be added here for them to get pages.
+fn subcommands(words: &[String]) -> Vec<App<'static, 'static>> {
+    match words.iter().map(|word| &word[..]).collect::<Vec<_>>()[..] {
+        [] => crate::details::subcommands(),
+        ["config"] => crate::config::command::details::subcommands(),
+        ["label"] => crate::label::details::subcommands(),
+        ["cache"] => crate::cache::details::subcommands(),
+        _ => Vec::new(),
+    }
+}
+
+/// Converts a list of arguments or subcommands, as rendered by clap with `NextLineHelp`, into a roff list. Each item
+/// is followed by its help on the next line. Arguments are told apart from their help by their leading `-` or `<`,
+/// since long-only options are indented further than the others; in a list of `subcommands`, the names are the lines
+/// that aren't indented as far as the help.
+fn items_to_roff(items: &str, subcommands: bool) -> String {
+    let mut roff = String::new();
+
+    for line in items.lines() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+
+        let is_item = if subcommands {
+            line.len() - line.trim_start().len() <= 4
+        } else {
+            trimmed.starts_with('-') || trimmed.starts_with('<')
+        };
+
+        if is_item {
+            roff.push_str(&format!(".TP\n\\fB{}\\fR\n", escape(trimmed)));
+        } else {
+            roff.push_str(&format!("{}\n", escape(trimmed)));
+        }
+    }
+
+    roff
+}
+
+/// Escapes `text` so that roff displays it literally.
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+
+    // A line starting with a dot or an apostrophe would be treated as a request.
+    if escaped.starts_with('.') || escaped.starts_with('\'') {
+        format!("\\&{}", escaped)
+    } else {
+        escaped
+    }
+}
+
+/// Details about this command.
+pub mod details {
+    use clap::{App, Arg};
+
+    /// This command's app definition.
+    pub fn app() -> App<'static, 'static> {
+        App::new(name())
+            .version(version())
+            .author(author())
+            .about(description())
+            .args(&args()[..])
+    }
+
+    /// This command's name.
+    fn name() -> &'static str {
+        "man"
+    }
+
+    /// This command's version.
+    fn version() -> &'static str {
+        env!("CARGO_PKG_VERSION")
+    }
+
+    /// This command's author(s).
+    fn author() -> &'static str {
+        env!("CARGO_PKG_AUTHORS")
+    }
+
+    /// This command's description.
+    fn description() -> &'static str {
+        "Print or install the man pages for ghtool and its commands"
+    }
+
+    /// This command's arguments.
+    fn args() -> Vec<Arg<'static, 'static>> {
+        vec![
+            Arg::with_name("page")
+                .index(1)
+                .value_name("PAGE")
+                .help("The page to print, e.g. \"ghtool-label-copy\". Defaults to \"ghtool\".")
+                .takes_value(true),
+            Arg::with_name("dir")
+                .long("dir")
+                .short("d")
+                .value_name("DIR")
+                .help("Write every page to DIR, e.g. \"/usr/local/share/man/man1\", instead of printing one")
+                .takes_value(true)
+                .conflicts_with("page"),
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(name: &str) -> String {
+        let pages = pages(crate::details::app());
+        let names = pages.iter().map(Page::name).collect::<Vec<_>>();
+
+        pages
+            .iter()
+            .find(|page| page.name() == name)
+            .map(|page| page.render(&names[..]))
+            .unwrap_or_else(|| panic!("There is no page named {}", name))
+    }
+
+    #[test]
+    fn has_a_page_for_every_visible_command() {
+        let names = pages(crate::details::app()).iter().map(Page::name).collect::<Vec<_>>();
+
+        let expected = [
+            "ghtool",
+            "ghtool-label-copy",
+            "ghtool-config-init",
+            "ghtool-config-validate",
+            "ghtool-cache-clear",
+        ];
+
+        for name in &expected {
+            assert!(names.iter().any(|other| other == name), "{} is missing from {:?}", name, names);
+        }
+
+        assert!(!names.iter().any(|name| name.contains("complete-args")));
+    }
+
+    #[test]
+    fn has_a_page_for_every_subcommand_in_the_help() {
+        let pages = pages(crate::details::app());
+        let names = pages.iter().map(Page::name).collect::<Vec<_>>();
+
+        for page in &pages {
+            let roff = page.render(&names[..]);
+            let section = match roff.split(".SH SUBCOMMANDS\n").nth(1) {
+                Some(rest) => rest.split("\n.SH ").next().unwrap_or_default(),
+                None => continue,
+            };
+
+            for item in section.split(".TP\n\\fB").skip(1) {
+                let subcommand = item.split("\\fR").next().unwrap_or_default().replace("\\-", "-");
+                let name = format!("{}-{}", page.name(), subcommand);
+                assert!(subcommand == "help" || names.contains(&name), "{} is missing from {:?}", name, names);
+            }
+        }
+    }
+
+    #[test]
+    fn renders_every_argument_as_a_list_item() {
+        let roff = page("ghtool-label-copy");
+
+        assert!(roff.starts_with(".TH \"GHTOOL-LABEL-COPY\" 1"));
+        assert!(roff.contains(".TP\n\\fB\\-\\-resume\\fR\nCarry on copying"), "{}", roff);
+        assert!(roff.contains(".TP\n\\fB\\-f, \\-\\-from <FROM>\\fR\nThe repository to copy from"), "{}", roff);
+
+        let roff = page("ghtool");
+        for option in &["\\-\\-no\\-cache", "\\-\\-cacert <PATH>", "\\-\\-color <WHEN>", "\\-\\-config <PATH>"] {
+            assert!(roff.contains(&format!(".TP\n\\fB{}\\fR\n", option)[..]), "{} in {}", option, roff);
+        }
+    }
+
+    #[test]
+    fn renders_subcommands_as_list_items() {
+        let roff = page("ghtool-config");
+
+        assert!(roff.contains(".SH SUBCOMMANDS\n"));
+        assert!(roff.contains(".TP\n\\fBinit\\fR\n"), "{}", roff);
+        assert!(roff.contains("\\fBghtool\\-config\\-init\\fR(1)"));
+    }
+}
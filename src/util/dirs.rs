@@ -0,0 +1,43 @@
+//
+//  util/dirs.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+use std::env;
+use std::path::PathBuf;
+
+/// Gets the directory that ghtool keeps its caches and other state in, or `None` if it isn't possible to determine the
+/// user's home directory.
+pub fn cache_dir() -> Option<PathBuf> {
+    // As with XDG_CONFIG_HOME, the XDG Base Directory specification says that relative paths should be ignored.
+    env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::home_dir().map(|home| home.join(".cache")))
+        .map(|cache_dir| cache_dir.join("ghtool"))
+}
+
+/// Gets the directory that ghtool keeps data that shouldn't be thrown away in, like the journal of changes it has made,
+/// or `None` if it isn't possible to determine the user's home directory.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .filter(|path| path.is_absolute())
+        .or_else(|| env::home_dir().map(|home| home.join(".local").join("share")))
+        .map(|data_dir| data_dir.join("ghtool"))
+}
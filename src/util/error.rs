@@ -18,21 +18,42 @@
 //  limitations under the License.
 //
 
+use reqwest::Error as HttpError;
+use reqwest::StatusCode;
+use serde_json::Error as JsonError;
+
 use std::error::Error;
 use std::fmt;
+use std::io::Error as IoError;
+use std::path::PathBuf;
+
+use crate::auth::error::AuthError;
+use crate::util::exit::ExitCode;
 
 /// Errors that could arise in the process of parsing command-line arguments.
 #[derive(Debug)]
-pub enum ArgError<'a> {
+pub enum ArgError {
     /// No subcommand was provided, and one is required.
     NoSubcommand,
     /// No value was provided for the argument with name `arg`.
-    NoValue { arg: &'a str },
+    NoValue { arg: &'static str },
     /// An invalid value `value` was provided for the argument with name `arg`.
-    InvalidValue { arg: &'a str, value: &'a str },
+    InvalidValue { arg: &'static str, value: String },
+    /// The value `value` provided for the argument with name `arg` isn't a valid repository.
+    InvalidRepo {
+        arg: &'static str,
+        value: String,
+        error: RepoError,
+    },
+    /// The value `value` provided for the argument with name `arg` isn't a valid repository selector.
+    InvalidSelector {
+        arg: &'static str,
+        value: String,
+        error: SelectorError,
+    },
 }
 
-impl<'a> fmt::Display for ArgError<'a> {
+impl fmt::Display for ArgError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
             ArgError::NoSubcommand => write!(f, "No subcommand was provided"),
@@ -40,16 +61,206 @@ impl<'a> fmt::Display for ArgError<'a> {
             ArgError::InvalidValue { ref arg, ref value } => {
                 write!(f, "Invalid value {} provided for argument {}", value, arg)
             }
+            ArgError::InvalidRepo {
+                ref arg,
+                ref value,
+                ref error,
+            } => write!(f, "Invalid repository {} provided for argument {}: {}", value, arg, error),
+            ArgError::InvalidSelector {
+                ref arg,
+                ref value,
+                ref error,
+            } => write!(f, "Invalid repositories {} provided for argument {}: {}", value, arg, error),
         }
     }
 }
 
-impl<'a> Error for ArgError<'a> {
+impl ArgError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::Usage
+    }
+}
+
+impl Error for ArgError {
     fn description(&self) -> &str {
         match *self {
             ArgError::NoSubcommand => "NoSubcommand",
             ArgError::NoValue { .. } => "NoValue",
             ArgError::InvalidValue { .. } => "InvalidValue",
+            ArgError::InvalidRepo { .. } => "InvalidRepo",
+            ArgError::InvalidSelector { .. } => "InvalidSelector",
+        }
+    }
+}
+
+/// Errors that could arise in the process of parsing a repository.
+#[derive(Debug)]
+pub enum RepoError {
+    /// The string was empty.
+    Empty,
+    /// The string is neither in the form "user/repository" nor a URL.
+    InvalidFormat(String),
+    /// The URL has a scheme other than `https`, `http`, `ssh` or `git`.
+    UnsupportedScheme(String),
+    /// The path has more components than just the user and repository, e.g. "user/repository/tree/master".
+    TrailingPath(String),
+    /// The owner of the repository isn't a valid GitHub username.
+    InvalidOwner(String),
+    /// The name of the repository isn't a valid GitHub repository name.
+    InvalidName(String),
+}
+
+impl fmt::Display for RepoError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RepoError::Empty => write!(f, "No repository provided"),
+            RepoError::InvalidFormat(ref string) => write!(
+                f,
+                "{} is not in the format \"user/repository\" and is not a repository URL",
+                string
+            ),
+            RepoError::UnsupportedScheme(ref scheme) => write!(f, "Unsupported URL scheme {}", scheme),
+            RepoError::TrailingPath(ref string) => {
+                write!(f, "{} has extra path components after the repository name", string)
+            }
+            RepoError::InvalidOwner(ref owner) => write!(f, "{} is not a valid username", owner),
+            RepoError::InvalidName(ref name) => write!(f, "{} is not a valid repository name", name),
+        }
+    }
+}
+
+impl Error for RepoError {
+    fn description(&self) -> &str {
+        match *self {
+            RepoError::Empty => "Empty",
+            RepoError::InvalidFormat(_) => "InvalidFormat",
+            RepoError::UnsupportedScheme(_) => "UnsupportedScheme",
+            RepoError::TrailingPath(_) => "TrailingPath",
+            RepoError::InvalidOwner(_) => "InvalidOwner",
+            RepoError::InvalidName(_) => "InvalidName",
+        }
+    }
+}
+
+/// Errors that could arise in the process of making a request to the GitHub REST API.
+#[derive(Debug)]
+pub enum RestError {
+    /// Credentials for the request could not be obtained.
+    AuthError(AuthError),
+    /// The request failed, or the HTTP client couldn't be set up.
+    HttpError(HttpError),
+    /// The runtime that requests are sent on couldn't be started.
+    IoError(IoError),
+    /// GitHub didn't respond to the request in time.
+    Timeout,
+    /// GitHub responded to the request with an error.
+    StatusError { status: StatusCode, body: String },
+    /// GitHub's response could not be parsed.
+    JsonError(JsonError),
+}
+
+impl fmt::Display for RestError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            RestError::AuthError(ref auth_err) => write!(f, "Authentication error: {}", auth_err),
+            RestError::HttpError(ref http_err) => write!(f, "HTTP error: {}", http_err),
+            RestError::IoError(ref io_err) => write!(f, "I/O error: {}", io_err),
+            RestError::Timeout => write!(f, "GitHub didn't respond in time"),
+            RestError::StatusError { ref status, ref body } => write!(f, "GitHub returned {}: {}", status, body),
+            RestError::JsonError(ref json_err) => write!(f, "Invalid response: {}", json_err),
+        }
+    }
+}
+
+impl RestError {
+    pub fn exit_code(&self) -> ExitCode {
+        match *self {
+            RestError::AuthError(_) => ExitCode::Auth,
+            RestError::StatusError { status, ref body } => ExitCode::from_status(status, &body[..]),
+            _ => ExitCode::Failure,
+        }
+    }
+}
+
+impl Error for RestError {
+    fn description(&self) -> &str {
+        match *self {
+            RestError::AuthError(_) => "AuthError",
+            RestError::HttpError(_) => "HttpError",
+            RestError::IoError(_) => "IoError",
+            RestError::Timeout => "Timeout",
+            RestError::StatusError { .. } => "StatusError",
+            RestError::JsonError(_) => "JsonError",
+        }
+    }
+}
+
+/// Errors that could arise in the process of parsing or expanding a repository selector.
+#[derive(Debug)]
+pub enum SelectorError {
+    /// The selector names a single repository, which is invalid.
+    RepoError(RepoError),
+    /// The selector is of a kind that requires an owner, e.g. "@team:org/team", but none was given, either in the
+    /// selector or as the `defaults.org` configuration key.
+    MissingOwner(String),
+    /// The selector starts with "@", but isn't one of the known kinds.
+    UnknownKind(String),
+    /// The file named by an "@file:" selector could not be read.
+    IoError(PathBuf, IoError),
+    /// An "@file:" selector refers to a file that is already being read.
+    RecursiveFile(PathBuf),
+    /// A ":group" selector names a group that isn't defined in the `[groups]` configuration table.
+    UnknownGroup(String),
+    /// A ":group" selector names a group that contains itself.
+    RecursiveGroup(String),
+}
+
+impl fmt::Display for SelectorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SelectorError::RepoError(ref repo_err) => write!(f, "{}", repo_err),
+            SelectorError::MissingOwner(ref selector) => {
+                write!(
+                    f,
+                    "{} must include an owner, e.g. \"@team:org/team\", unless defaults.org is set",
+                    selector
+                )
+            }
+            SelectorError::UnknownKind(ref kind) => write!(
+                f,
+                "Unknown selector {}; expected @topic:, @team: or @file:",
+                kind
+            ),
+            SelectorError::IoError(ref path, ref io_err) => {
+                write!(f, "Unable to read {}: {}", path.display(), io_err)
+            }
+            SelectorError::RecursiveFile(ref path) => write!(f, "{} includes itself", path.display()),
+            SelectorError::UnknownGroup(ref name) => write!(
+                f,
+                "Unknown group \"{}\"; groups are defined in the [groups] table of the configuration files",
+                name
+            ),
+            SelectorError::RecursiveGroup(ref name) => write!(f, "Group \"{}\" includes itself", name),
+        }
+    }
+}
+
+impl SelectorError {
+    pub fn exit_code(&self) -> ExitCode {
+        ExitCode::Usage
+    }
+}
+
+impl Error for SelectorError {
+    fn description(&self) -> &str {
+        match *self {
+            SelectorError::RepoError(_) => "RepoError",
+            SelectorError::MissingOwner(_) => "MissingOwner",
+            SelectorError::UnknownKind(_) => "UnknownKind",
+            SelectorError::IoError(..) => "IoError",
+            SelectorError::RecursiveFile(_) => "RecursiveFile",
+            SelectorError::UnknownGroup(_) => "UnknownGroup",
+            SelectorError::RecursiveGroup(_) => "RecursiveGroup",
         }
     }
 }
@@ -0,0 +1,67 @@
+//
+//  util/exit.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The codes that ghtool exits with.
+//!
+//! Scripts rely on these, so they're part of ghtool's interface: new codes may be added, but existing codes must never
+//! change meaning. They're documented in the README.
+
+use reqwest::StatusCode;
+
+/// The code that ghtool exits with.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    /// Everything succeeded.
+    Success = 0,
+    /// An error that doesn't fall into any of the other categories.
+    Failure = 1,
+    /// The command-line arguments were invalid.
+    Usage = 2,
+    /// The configuration files are missing or invalid.
+    Config = 3,
+    /// Credentials couldn't be obtained, or GitHub rejected them.
+    Auth = 4,
+    /// A repository or other resource doesn't exist, or the credentials don't grant access to it.
+    NotFound = 5,
+    /// The command did some of what it was asked to, but not all of it.
+    PartialFailure = 6,
+    /// The GitHub API rate limit has been exhausted.
+    RateLimited = 7,
+}
+
+impl ExitCode {
+    /// The numeric value of this code.
+    pub fn code(&self) -> i32 {
+        *self as i32
+    }
+
+    /// Works out the code for a request that GitHub responded to with `status`. `message` is the body of the response,
+    /// which is the only way to tell a rate limit apart from a lack of permission, since both have the status 403.
+    pub fn from_status(status: StatusCode, message: &str) -> ExitCode {
+        match status {
+            StatusCode::UNAUTHORIZED => ExitCode::Auth,
+            StatusCode::FORBIDDEN if message.to_lowercase().contains("rate limit") => ExitCode::RateLimited,
+            StatusCode::FORBIDDEN => ExitCode::Auth,
+            StatusCode::NOT_FOUND => ExitCode::NotFound,
+            StatusCode::TOO_MANY_REQUESTS => ExitCode::RateLimited,
+            _ => ExitCode::Failure,
+        }
+    }
+}
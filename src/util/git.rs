@@ -0,0 +1,263 @@
+//
+//  util/git.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Functionality for finding out about the git checkout that ghtool is being run in, so that commands can act on the
+//! GitHub repository it was cloned from without being told which one that is.
+
+use std::env;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use crate::util::repo::Repo;
+
+/// A remote in a git repository's configuration.
+#[derive(Clone, Debug)]
+pub struct Remote {
+    /// The name of the remote, e.g. "origin".
+    pub name: String,
+    /// The URL of the remote.
+    pub url: String,
+}
+
+/// Works out which GitHub repository the git checkout containing the working directory belongs to.
+///
+/// Only remotes that point to a repository on one of `hosts` are considered to point to a GitHub repository. The
+/// remotes named in `preferred` are tried in order, and the first one that points to a GitHub repository is used. If
+/// none of them do, the first remote in the checkout's configuration that points to a GitHub repository is used
+/// instead. Returns `None` if the working directory isn't inside a git checkout, or if none of its remotes point to a
+/// GitHub repository.
+pub fn infer_repo(preferred: &[String], hosts: &[String]) -> Option<Repo> {
+    let remotes = match current_remotes() {
+        Some(remotes) => remotes,
+        None => {
+            debug!("Not inside a git checkout; unable to infer repository");
+            return None;
+        }
+    };
+
+    let preferred_remotes = preferred
+        .iter()
+        .filter_map(|name| remotes.iter().find(|remote| &remote.name == name));
+
+    let repo = preferred_remotes
+        .chain(remotes.iter())
+        .filter_map(|remote| {
+            Repo::from_remote_url(&remote.url[..])
+                .ok()
+                .map(|repo| (remote, repo))
+        })
+        .find(|(_, repo)| {
+            hosts
+                .iter()
+                .any(|host| host.eq_ignore_ascii_case(&repo.host[..]))
+        });
+
+    match repo {
+        Some((remote, repo)) => {
+            info!("Using repository {} from git remote \"{}\"", repo, remote.name);
+            Some(repo)
+        }
+        None => {
+            debug!("None of the git remotes in this checkout point to a GitHub repository");
+            None
+        }
+    }
+}
+
+/// Returns the remotes of the git checkout containing the working directory, in the order in which they appear in its
+/// configuration, or `None` if the working directory isn't inside a git checkout.
+pub fn current_remotes() -> Option<Vec<Remote>> {
+    let git_dir = match env::var_os("GIT_DIR") {
+        Some(git_dir) => PathBuf::from(git_dir),
+        None => env::current_dir().ok().and_then(|dir| find_git_dir(&dir))?,
+    };
+
+    let mut contents = String::new();
+    File::open(common_dir(&git_dir).join("config"))
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .ok()?;
+
+    Some(parse_remotes(&contents[..]))
+}
+
+/// Finds the git directory of the checkout containing `start`, by searching `start` and each of its ancestors for a
+/// `.git` directory, or a `.git` file pointing to one (as used by worktrees and submodules).
+fn find_git_dir(start: &Path) -> Option<PathBuf> {
+    for dir in start.ancestors() {
+        let dot_git = dir.join(".git");
+
+        if dot_git.is_dir() {
+            return Some(dot_git);
+        } else if dot_git.is_file() {
+            let mut contents = String::new();
+            File::open(&dot_git)
+                .and_then(|mut file| file.read_to_string(&mut contents))
+                .ok()?;
+
+            return contents
+                .lines()
+                .filter_map(|line| line.trim().split_once("gitdir:").map(|x| x.1))
+                .next()
+                .map(|git_dir| dir.join(git_dir.trim()));
+        }
+    }
+
+    None
+}
+
+/// Returns the directory containing the configuration shared by all of a repository's worktrees. For a worktree, this
+/// is the git directory of the main checkout; otherwise, it's just `git_dir`.
+fn common_dir(git_dir: &Path) -> PathBuf {
+    let mut contents = String::new();
+    let has_common_dir = File::open(git_dir.join("commondir"))
+        .and_then(|mut file| file.read_to_string(&mut contents))
+        .is_ok();
+
+    if has_common_dir {
+        git_dir.join(contents.trim())
+    } else {
+        git_dir.to_path_buf()
+    }
+}
+
+/// Parses the remotes out of the contents of a git configuration file.
+fn parse_remotes(contents: &str) -> Vec<Remote> {
+    let mut remotes: Vec<Remote> = Vec::new();
+    let mut current_remote: Option<String> = None;
+
+    for line in contents.lines() {
+        let line = line.trim();
+
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        // Section headers look like `[remote "origin"]`.
+        if line.starts_with('[') {
+            let header = line.trim_matches(|c: char| c == '[' || c == ']');
+            current_remote = header
+                .strip_prefix("remote ")
+                .map(|name| name.trim().trim_matches('"').to_owned());
+
+            continue;
+        }
+
+        let name = match current_remote {
+            Some(ref name) => name,
+            None => continue,
+        };
+
+        let mut parts = line.splitn(2, '=');
+        let (key, value) = match (parts.next(), parts.next()) {
+            (Some(key), Some(value)) => (key.trim(), value.trim().trim_matches('"')),
+            _ => continue,
+        };
+
+        // Remotes can have more than one URL, but git only fetches from the first.
+        if key == "url" && !remotes.iter().any(|remote| &remote.name == name) {
+            remotes.push(Remote {
+                name: name.clone(),
+                url: value.to_owned(),
+            });
+        }
+    }
+
+    remotes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The name and URL of each remote in `contents`, in order.
+    fn remotes(contents: &str) -> Vec<(String, String)> {
+        parse_remotes(contents)
+            .into_iter()
+            .map(|remote| (remote.name, remote.url))
+            .collect()
+    }
+
+    fn pair(name: &str, url: &str) -> (String, String) {
+        (name.to_owned(), url.to_owned())
+    }
+
+    #[test]
+    fn parses_origin_and_upstream() {
+        let contents = "[core]\n\trepositoryformatversion = 0\n\tbare = false\n\
+                        [remote \"origin\"]\n\turl = git@github.com:alice/web.git\n\
+                        \tfetch = +refs/heads/*:refs/remotes/origin/*\n\
+                        [branch \"master\"]\n\tremote = origin\n\tmerge = refs/heads/master\n\
+                        [remote \"upstream\"]\n\turl = https://github.com/acme/web.git\n\
+                        \tfetch = +refs/heads/*:refs/remotes/upstream/*\n";
+
+        assert_eq!(
+            remotes(contents),
+            vec![
+                pair("origin", "git@github.com:alice/web.git"),
+                pair("upstream", "https://github.com/acme/web.git"),
+            ]
+        );
+    }
+
+    #[test]
+    fn uses_the_first_url_of_each_remote() {
+        // `git remote -v` lists each remote twice, for fetching and pushing, but it's only one remote.
+        let contents = "[remote \"origin\"]\n\turl = https://github.com/acme/web.git\n\
+                        \tpushurl = git@github.com:acme/web.git\n\turl = https://github.com/acme/mirror.git\n\
+                        [remote \"origin\"]\n\turl = https://github.com/acme/other.git\n";
+
+        assert_eq!(remotes(contents), vec![pair("origin", "https://github.com/acme/web.git")]);
+    }
+
+    #[test]
+    fn parses_scp_and_https_urls() {
+        let contents = "[remote \"ssh\"]\n\turl = git@github.com:acme/web.git\n\
+                        [remote \"https\"]\n\turl = \"https://github.com/acme/api\"\n";
+
+        let repos = parse_remotes(contents)
+            .iter()
+            .map(|remote| Repo::from_remote_url(&remote.url[..]).ok())
+            .collect::<Vec<_>>();
+
+        assert_eq!(repos, vec![Repo::from_string("acme/web").ok(), Repo::from_string("acme/api").ok()]);
+    }
+
+    #[test]
+    fn parses_enterprise_hosts() {
+        let contents = "[remote \"origin\"]\n\turl = git@github.example.com:acme/web.git\n\
+                        [remote \"backup\"]\n\turl = https://github.example.com:8443/acme/web\n";
+
+        let hosts = parse_remotes(contents)
+            .iter()
+            .filter_map(|remote| Repo::from_remote_url(&remote.url[..]).ok())
+            .map(|repo| repo.host)
+            .collect::<Vec<_>>();
+
+        assert_eq!(hosts, vec!["github.example.com", "github.example.com:8443"]);
+    }
+
+    #[test]
+    fn parses_no_remotes() {
+        assert!(remotes("").is_empty());
+        assert!(remotes("[core]\n\tbare = false\n# [remote \"origin\"]\n[branch \"master\"]\n\tremote = origin\n")
+            .is_empty());
+    }
+}
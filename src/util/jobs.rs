@@ -0,0 +1,136 @@
+//
+//  util/jobs.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Running the same request against many repositories or labels at once, with the `--jobs` argument.
+
+use std::collections::{BTreeMap, VecDeque};
+use std::panic;
+use std::sync::mpsc;
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+use crate::client::{Client, Connector};
+use crate::errors::Result;
+
+/// Runs `work` on each of `items`, on up to `jobs` threads at once, and passes the results to `done` in the same order
+/// as `items`.
+///
+/// `done` is called on the current thread with each result as soon as it, and every result before it, is ready. That
+/// way, output can be printed as work progresses, without its order depending on which thread happened to finish
+/// first.
+///
+/// Each thread uses its own client, created by the `Connector` from `client`. If there's only one job, only one item,
+/// or `client` can't be used from more than one thread, everything happens on the current thread using `client`.
+pub fn run<T, R, W, D>(jobs: usize, client: &mut dyn Client, items: Vec<T>, work: W, mut done: D) -> Result<()>
+where
+    T: Send + 'static,
+    R: Send + 'static,
+    W: Fn(&mut dyn Client, T) -> R + Send + Sync + 'static,
+    D: FnMut(R),
+{
+    let connector = if jobs > 1 && items.len() > 1 {
+        client.connector()
+    } else {
+        None
+    };
+
+    let connector: Arc<dyn Connector> = match connector {
+        Some(connector) => Arc::from(connector),
+        None => {
+            for item in items {
+                done(work(&mut *client, item));
+            }
+
+            return Ok(());
+        }
+    };
+
+    let total = items.len();
+    let threads = jobs.min(total);
+    debug!("Running {} items on {} threads", total, threads);
+
+    let queue = Arc::new(Mutex::new(items.into_iter().enumerate().collect::<VecDeque<_>>()));
+    let work = Arc::new(work);
+    let (sender, receiver) = mpsc::channel();
+
+    let handles = (0..threads)
+        .map(|_| {
+            let connector = connector.clone();
+            let queue = queue.clone();
+            let work = work.clone();
+            let sender = sender.clone();
+
+            thread::spawn(move || -> Result<()> {
+                let mut client = connector.connect()?;
+
+                loop {
+                    let next = queue.lock().unwrap_or_else(|poisoned| poisoned.into_inner()).pop_front();
+                    let (index, item) = match next {
+                        Some(next) => next,
+                        None => return Ok(()),
+                    };
+
+                    // If the receiver has gone, nothing is waiting for the results any more.
+                    if sender.send((index, work(&mut *client, item))).is_err() {
+                        return Ok(());
+                    }
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+
+    // Only the threads' senders should keep the channel open, so that it closes once they've all finished.
+    drop(sender);
+
+    // Results arrive in whatever order they're finished in, so each one is held on to until every result before it
+    // has been passed on.
+    let mut finished = BTreeMap::new();
+    let mut next = 0;
+
+    for (index, result) in receiver {
+        finished.insert(index, result);
+
+        while let Some(result) = finished.remove(&next) {
+            done(result);
+            next += 1;
+        }
+    }
+
+    let mut first_error = None;
+    for handle in handles {
+        match handle.join() {
+            Ok(Ok(())) => (),
+            Ok(Err(err)) => {
+                warn!("A job failed to start: {}", err);
+                if first_error.is_none() {
+                    first_error = Some(err);
+                }
+            }
+            Err(panic) => panic::resume_unwind(panic),
+        }
+    }
+
+    // A thread that couldn't create a client leaves its share of the work to the others, so it's only a problem if
+    // none of them could.
+    match first_error {
+        Some(err) if next < total => Err(err),
+        _ => Ok(()),
+    }
+}
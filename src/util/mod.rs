@@ -18,5 +18,14 @@
 //  limitations under the License.
 //
 
+pub mod dirs;
 pub mod error;
+pub mod exit;
+pub mod git;
+pub mod jobs;
+pub mod output;
+pub mod progress;
 pub mod repo;
+pub mod rest;
+pub mod selector;
+pub mod style;
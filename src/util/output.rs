@@ -0,0 +1,60 @@
+//
+//  util/output.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! The formats that commands can print their results in.
+//!
+//! In the JSON format, everything written to stdout is JSON: commands that produce a single result print one JSON
+//! document, and commands that stream results print one JSON document per line. Logs and errors are written to
+//! stderr, so they never get mixed up with the results.
+
+use clap::ArgMatches;
+use serde::Serialize;
+
+/// The format that commands print their results in.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Format {
+    /// Plain text, for people.
+    #[default]
+    Text,
+    /// JSON, for other programs.
+    Json,
+}
+
+impl Format {
+    /// Gets the format given by the top-level `--output` argument.
+    pub fn from_matches(matches: &ArgMatches) -> Format {
+        match matches.value_of("output") {
+            Some("json") => Format::Json,
+            _ => Format::Text,
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        *self == Format::Json
+    }
+}
+
+/// Prints `value` to stdout as a single line of JSON.
+pub fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string(value) {
+        Ok(json) => println!("{}", json),
+        Err(err) => error!("Unable to serialize output: {}", err),
+    }
+}
@@ -0,0 +1,165 @@
+//
+//  util/progress.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Progress bars for commands that make lots of requests, drawn on stderr.
+
+use log::{self, LogLevelFilter};
+
+use std::io::{self, Write};
+use std::time::{Duration, Instant};
+
+use crate::config::Config;
+use crate::util::style::Style;
+
+/// The width of the bar itself, in columns.
+const BAR_WIDTH: usize = 30;
+
+/// A progress bar, which shows how many items have been done out of the total, what's currently being worked on, how
+/// long is left, and how many requests can be made before the GitHub API rate limit is exhausted.
+///
+/// The bar is only drawn when stderr is a terminal, and never in the JSON format, in quiet mode, or when logs more
+/// verbose than warnings are turned on, since those would be drawn over it.
+pub struct Progress {
+    enabled: bool,
+    total: usize,
+    done: usize,
+    started: Instant,
+    /// What's currently being worked on, e.g. the repository that labels are being copied to.
+    message: String,
+    /// The number of requests that can be made before the rate limit is exhausted, if it's known.
+    rate_limit: Option<u32>,
+}
+
+impl Progress {
+    /// Creates a progress bar for `total` items, which is drawn if `config` allows it.
+    pub fn new(config: &Config, total: usize) -> Progress {
+        let enabled = !config.output().is_json()
+            && !config.quiet()
+            && log::max_log_level() <= LogLevelFilter::Warn
+            && Style::stderr(config.color()).is_terminal();
+
+        Progress {
+            enabled,
+            total,
+            done: 0,
+            started: Instant::now(),
+            message: String::new(),
+            rate_limit: None,
+        }
+    }
+
+    /// Sets what's currently being worked on.
+    pub fn set_message<S: Into<String>>(&mut self, message: S) {
+        self.message = message.into();
+        self.draw();
+    }
+
+    /// Sets the number of requests that can be made before the rate limit is exhausted.
+    pub fn set_rate_limit(&mut self, remaining: u32) {
+        self.rate_limit = Some(remaining);
+        self.draw();
+    }
+
+    /// Marks another item as done.
+    pub fn inc(&mut self) {
+        self.done = (self.done + 1).min(self.total);
+        self.draw();
+    }
+
+    /// Hides the bar while `f` runs, so that it can print without the bar getting in the way, then draws it again.
+    pub fn suspend<F: FnOnce() -> T, T>(&self, f: F) -> T {
+        self.clear();
+        let result = f();
+        self.draw();
+        result
+    }
+
+    /// Removes the bar for good.
+    pub fn finish(&mut self) {
+        self.clear();
+        self.enabled = false;
+    }
+
+    fn clear(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        }
+    }
+
+    fn draw(&self) {
+        if !self.enabled {
+            return;
+        }
+
+        let filled = (BAR_WIDTH * self.done).checked_div(self.total).unwrap_or(BAR_WIDTH);
+
+        let mut line = format!(
+            "[{}{}] {}/{}",
+            "=".repeat(filled),
+            " ".repeat(BAR_WIDTH - filled),
+            self.done,
+            self.total
+        );
+
+        if !self.message.is_empty() {
+            line.push_str(&format!(" {}", self.message));
+        }
+
+        if let Some(eta) = self.eta() {
+            line.push_str(&format!(", {} left", format_duration(eta)));
+        }
+
+        if let Some(remaining) = self.rate_limit {
+            line.push_str(&format!(" ({} requests left)", remaining));
+        }
+
+        eprint!("\r\x1b[2K{}", line);
+        let _ = io::stderr().flush();
+    }
+
+    /// Estimates how long is left, based on how long the items that are done took.
+    fn eta(&self) -> Option<Duration> {
+        if self.done == 0 || self.done >= self.total {
+            return None;
+        }
+
+        let elapsed = self.started.elapsed();
+        let elapsed_ms = elapsed.as_secs() * 1000 + u64::from(elapsed.subsec_millis());
+        let remaining_ms = elapsed_ms / self.done as u64 * (self.total - self.done) as u64;
+
+        Some(Duration::from_millis(remaining_ms))
+    }
+}
+
+impl Drop for Progress {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Formats a duration as "m:ss", or "h:mm:ss" if it's an hour or more.
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match (seconds / 3600, seconds / 60 % 60, seconds % 60) {
+        (0, minutes, seconds) => format!("{}:{:02}", minutes, seconds),
+        (hours, minutes, seconds) => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+    }
+}
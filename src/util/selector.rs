@@ -0,0 +1,309 @@
+//
+//  util/selector.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Repository selectors, which allow commands to act on whole sets of repositories at once.
+
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::client::Client;
+use crate::errors;
+use crate::util::error::SelectorError;
+use crate::util::repo::Repo;
+
+/// Named groups of selectors, as defined in the `[groups]` table of the configuration files.
+pub type Groups = BTreeMap<String, Vec<String>>;
+
+/// A way of selecting one or more repositories.
+#[derive(Clone, Debug)]
+pub enum Selector {
+    /// A single repository, e.g. "acme/web".
+    Repo(Repo),
+    /// Every repository belonging to a user or organisation, e.g. "acme/*".
+    Owner(String),
+    /// Every repository belonging to an owner with a topic, e.g. "@topic:acme/backend".
+    Topic { owner: String, topic: String },
+    /// Every repository that a team has access to, e.g. "@team:acme/platform".
+    Team { org: String, team: String },
+    /// The repositories selected by each line of a file, e.g. "@file:repos.txt".
+    File {
+        path: PathBuf,
+        default_owner: Option<String>,
+        groups: Groups,
+    },
+    /// The repositories selected by each member of a group from the configuration files, e.g. ":frontend".
+    Group { name: String, selectors: Vec<Selector> },
+}
+
+impl Selector {
+    /// Attempts to parse a `String` to create a `Selector`. The string can be in any of these forms:
+    ///
+    /// - Anything accepted by `Repo::from_string`, which selects a single repository.
+    /// - "owner/*", which selects every repository belonging to a user or organisation.
+    /// - "@topic:owner/topic", which selects every repository belonging to an owner with a topic.
+    /// - "@team:org/team", which selects every repository that a team has access to.
+    /// - "@file:path", which selects the repositories selected by each line of a file.
+    /// - ":group", which selects the repositories selected by each member of one of `groups`.
+    ///
+    /// If `default_owner` is provided, it's used as the owner wherever one is missing, e.g. "web" or "*".
+    pub fn from_string(string: &str, default_owner: Option<&str>, groups: &Groups) -> Result<Selector, SelectorError> {
+        Selector::from_string_within(string, default_owner, groups, &mut Vec::new())
+    }
+
+    /// Parses a selector. `active_groups` is the stack of groups whose members are currently being parsed, which is
+    /// used to detect groups that include themselves.
+    fn from_string_within(
+        string: &str,
+        default_owner: Option<&str>,
+        groups: &Groups,
+        active_groups: &mut Vec<String>,
+    ) -> Result<Selector, SelectorError> {
+        let string = string.trim();
+
+        if let Some(name) = string.strip_prefix(':') {
+            let members = groups.get(name).ok_or(SelectorError::UnknownGroup(name.to_owned()))?;
+
+            if active_groups.iter().any(|active| active == name) {
+                return Err(SelectorError::RecursiveGroup(name.to_owned()));
+            }
+
+            active_groups.push(name.to_owned());
+            let selectors = members
+                .iter()
+                .map(|member| Selector::from_string_within(member, default_owner, groups, active_groups))
+                .collect::<Result<Vec<_>, _>>()?;
+            active_groups.pop();
+
+            return Ok(Selector::Group {
+                name: name.to_owned(),
+                selectors,
+            });
+        }
+
+        if string.starts_with('@') {
+            let colon = string.find(':').unwrap_or(string.len());
+            let (kind, value) = (&string[..colon], string[colon..].trim_start_matches(':'));
+
+            return match kind {
+                // Searching every repository on GitHub for a topic would find far more than anyone wants to change.
+                "@topic" => match split_owner(value, default_owner) {
+                    (Some(owner), topic) => Ok(Selector::Topic { owner, topic }),
+                    (None, _) => Err(SelectorError::MissingOwner(string.to_owned())),
+                },
+                "@team" => match split_owner(value, default_owner) {
+                    (Some(org), team) => Ok(Selector::Team { org, team }),
+                    (None, _) => Err(SelectorError::MissingOwner(string.to_owned())),
+                },
+                "@file" => Ok(Selector::File {
+                    path: PathBuf::from(value),
+                    default_owner: default_owner.map(str::to_owned),
+                    groups: groups.clone(),
+                }),
+                _ => Err(SelectorError::UnknownKind(kind.to_owned())),
+            };
+        }
+
+        if string == "*" {
+            return default_owner
+                .map(|owner| Selector::Owner(owner.to_owned()))
+                .ok_or(SelectorError::MissingOwner(string.to_owned()));
+        }
+
+        if let Some(owner) = string.strip_suffix("/*") {
+            return Ok(Selector::Owner(owner.to_owned()));
+        }
+
+        Repo::from_string_with_default_owner(string, default_owner)
+            .map(Selector::Repo)
+            .map_err(SelectorError::RepoError)
+    }
+
+    /// Expands this selector into the repositories it selects, using the GitHub API where necessary.
+    pub fn expand(&self, client: &mut dyn Client) -> errors::Result<Vec<Repo>> {
+        self.expand_within(client, &mut Vec::new())
+    }
+
+    /// Expands this selector. `files` is the stack of files currently being read for "@file:" selectors, which is
+    /// used to detect files that include themselves.
+    fn expand_within(&self, client: &mut dyn Client, files: &mut Vec<PathBuf>) -> errors::Result<Vec<Repo>> {
+        match *self {
+            Selector::Repo(ref repo) => Ok(vec![repo.clone()]),
+            Selector::Owner(ref owner) => {
+                info!("Finding repositories belonging to {}...", owner);
+                client.owner_repos(&owner[..])
+            }
+            Selector::Topic {
+                ref owner,
+                ref topic,
+            } => {
+                info!("Finding repositories with topic {}...", topic);
+                client.topic_repos(&owner[..], &topic[..])
+            }
+            Selector::Team { ref org, ref team } => {
+                info!("Finding repositories that {}/{} has access to...", org, team);
+                client.team_repos(&org[..], &team[..])
+            }
+            Selector::File {
+                ref path,
+                ref default_owner,
+                ref groups,
+            } => {
+                let canonical = path.canonicalize()
+                    .map_err(|err| SelectorError::IoError(path.clone(), err))?;
+                if files.contains(&canonical) {
+                    return Err(SelectorError::RecursiveFile(path.clone()).into());
+                }
+
+                let mut contents = String::new();
+                File::open(&canonical)
+                    .and_then(|mut file| file.read_to_string(&mut contents))
+                    .map_err(|err| SelectorError::IoError(path.clone(), err))?;
+
+                files.push(canonical.clone());
+
+                let mut repos = Vec::new();
+                for line in contents.lines().map(str::trim) {
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+
+                    let default_owner = default_owner.as_ref().map(|owner| &owner[..]);
+                    let selector = match Selector::from_string(line, default_owner, groups)? {
+                        // Files named in a file are relative to the directory containing it.
+                        Selector::File {
+                            path: ref nested,
+                            ref default_owner,
+                            ref groups,
+                        } if nested.is_relative() =>
+                        {
+                            Selector::File {
+                                path: canonical.parent().map(|dir| dir.join(nested)).unwrap_or(nested.clone()),
+                                default_owner: default_owner.clone(),
+                                groups: groups.clone(),
+                            }
+                        }
+                        selector => selector,
+                    };
+
+                    repos.extend(selector.expand_within(client, files)?);
+                }
+
+                files.pop();
+
+                Ok(repos)
+            }
+            Selector::Group { ref selectors, .. } => {
+                let mut repos = Vec::new();
+                for selector in selectors {
+                    repos.extend(selector.expand_within(client, files)?);
+                }
+
+                Ok(repos)
+            }
+        }
+    }
+}
+
+/// Expands each of `selectors`, returning every repository selected by at least one of them. Repositories are returned
+/// in the order in which they were first selected.
+pub fn expand_all(selectors: &[Selector], client: &mut dyn Client) -> errors::Result<Vec<Repo>> {
+    let mut repos: Vec<Repo> = Vec::new();
+
+    for selector in selectors {
+        for repo in selector.expand(client)? {
+            if !repos.contains(&repo) {
+                repos.push(repo);
+            }
+        }
+    }
+
+    Ok(repos)
+}
+
+/// Splits "owner/name" into its owner and name. If there's no owner, `default_owner` is used instead.
+fn split_owner(value: &str, default_owner: Option<&str>) -> (Option<String>, String) {
+    match value.find('/') {
+        Some(slash) => (Some(value[..slash].to_owned()), value[slash + 1..].to_owned()),
+        None => (default_owner.map(str::to_owned), value.to_owned()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::fake::FakeClient;
+
+    fn repo(name: &str) -> Repo {
+        Repo::from_string(name).unwrap()
+    }
+
+    #[test]
+    fn expands_owner_selectors() {
+        let mut client = FakeClient::new()
+            .with_repo(&repo("acme/api"))
+            .with_repo(&repo("acme/web"))
+            .with_repo(&repo("other/web"));
+
+        let selector = Selector::from_string("acme/*", None, &Groups::new()).unwrap();
+
+        assert_eq!(selector.expand(&mut client).unwrap(), vec![repo("acme/api"), repo("acme/web")]);
+    }
+
+    #[test]
+    fn expands_groups_without_duplicates() {
+        let mut client = FakeClient::new().with_repo(&repo("acme/api")).with_repo(&repo("acme/web"));
+
+        let mut groups = Groups::new();
+        groups.insert("backend".to_owned(), vec!["acme/api".to_owned()]);
+        groups.insert("all".to_owned(), vec![":backend".to_owned(), "acme/*".to_owned()]);
+
+        let selectors = [Selector::from_string(":all", None, &groups).unwrap(),
+            Selector::from_string("api", Some("acme"), &groups).unwrap()];
+
+        assert_eq!(expand_all(&selectors[..], &mut client).unwrap(), vec![repo("acme/api"), repo("acme/web")]);
+    }
+
+    #[test]
+    fn requires_an_owner_for_topics() {
+        match Selector::from_string("@topic:backend", None, &Groups::new()) {
+            Err(SelectorError::MissingOwner(ref selector)) => assert_eq!(selector, "@topic:backend"),
+            result => panic!("Expected a missing owner error, got {:?}", result),
+        }
+
+        match Selector::from_string("@topic:backend", Some("acme"), &Groups::new()) {
+            Ok(Selector::Topic { ref owner, ref topic }) => assert_eq!((&owner[..], &topic[..]), ("acme", "backend")),
+            result => panic!("Expected a topic selector, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_recursive_groups() {
+        let mut groups = Groups::new();
+        groups.insert("a".to_owned(), vec![":b".to_owned()]);
+        groups.insert("b".to_owned(), vec![":a".to_owned()]);
+
+        match Selector::from_string(":a", None, &groups) {
+            Err(SelectorError::RecursiveGroup(ref name)) => assert_eq!(name, "a"),
+            result => panic!("Expected a recursive group error, got {:?}", result),
+        }
+    }
+}
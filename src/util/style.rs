@@ -0,0 +1,282 @@
+//
+//  util/style.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Styling for text printed for people to read: colours, markers for changes, label swatches and aligned tables.
+//!
+//! Everything here produces `Cell`s, which know how wide they'll appear on the terminal regardless of any escape
+//! sequences they contain, so that they can be lined up in a `Table`.
+
+use clap::ArgMatches;
+
+use std::env;
+use std::fmt;
+
+/// Whether to print text in colour, as given by the top-level `--color` argument.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Use colour if the text is being printed to a terminal.
+    #[default]
+    Auto,
+    /// Always use colour.
+    Always,
+    /// Never use colour.
+    Never,
+}
+
+impl ColorChoice {
+    /// Gets the choice given by the top-level `--color` argument. If it isn't given, colour is turned off when the
+    /// `NO_COLOR` environment variable is set to anything other than an empty string.
+    pub fn from_matches(matches: &ArgMatches) -> ColorChoice {
+        match matches.value_of("color") {
+            Some("always") => ColorChoice::Always,
+            Some("never") => ColorChoice::Never,
+            _ if env::var_os("NO_COLOR").is_some_and(|value| !value.is_empty()) => ColorChoice::Never,
+            _ => ColorChoice::Auto,
+        }
+    }
+}
+
+/// A stream that text can be printed to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// A kind of change made to something on GitHub, which is shown next to it with a coloured marker.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Change {
+    Create,
+    Update,
+    Delete,
+    /// A change that couldn't be made.
+    Fail,
+}
+
+impl Change {
+    /// The marker shown next to something that this change was made to.
+    fn marker(&self) -> &'static str {
+        match *self {
+            Change::Create => "+",
+            Change::Update => "~",
+            Change::Delete => "-",
+            Change::Fail => "!",
+        }
+    }
+
+    /// The SGR parameters for the colour of this change's marker.
+    fn color(&self) -> &'static str {
+        match *self {
+            Change::Create => "1;32",
+            Change::Update => "1;33",
+            Change::Delete | Change::Fail => "1;31",
+        }
+    }
+}
+
+/// The styling for text printed to one stream.
+#[derive(Clone, Copy, Debug)]
+pub struct Style {
+    /// Whether to use colour.
+    color: bool,
+    /// Whether the stream is a terminal.
+    terminal: bool,
+}
+
+impl Style {
+    /// Creates the styling for text printed to `stream`, following `choice`.
+    pub fn new(choice: ColorChoice, stream: Stream) -> Style {
+        let terminal = atty::is(match stream {
+            Stream::Stdout => atty::Stream::Stdout,
+            Stream::Stderr => atty::Stream::Stderr,
+        });
+
+        let color = match choice {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => terminal && env::var("TERM").map(|term| term != "dumb").unwrap_or(true),
+        };
+
+        Style { color, terminal }
+    }
+
+    /// Creates the styling for text printed to stdout.
+    pub fn stdout(choice: ColorChoice) -> Style {
+        Style::new(choice, Stream::Stdout)
+    }
+
+    /// Creates the styling for text printed to stderr.
+    pub fn stderr(choice: ColorChoice) -> Style {
+        Style::new(choice, Stream::Stderr)
+    }
+
+    /// Whether the stream is a terminal, and so is being read by a person rather than another program.
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// Wraps `text` in the SGR escape sequence with the parameters `params`, if colour is turned on.
+    fn paint(&self, params: &str, text: &str) -> Cell {
+        if self.color {
+            Cell {
+                text: format!("\x1b[{}m{}\x1b[0m", params, text),
+                width: width(text),
+            }
+        } else {
+            Cell::from(text)
+        }
+    }
+
+    pub fn bold(&self, text: &str) -> Cell {
+        self.paint("1", text)
+    }
+
+    pub fn dim(&self, text: &str) -> Cell {
+        self.paint("2", text)
+    }
+
+    pub fn error(&self, text: &str) -> Cell {
+        self.paint("1;31", text)
+    }
+
+    pub fn hint(&self, text: &str) -> Cell {
+        self.paint("36", text)
+    }
+
+    /// The coloured marker for `change`.
+    pub fn marker(&self, change: Change) -> Cell {
+        self.paint(change.color(), change.marker())
+    }
+
+    /// A label's name, drawn as a swatch in the label's colour the way GitHub draws it, if colour is turned on.
+    /// `color` is the label's colour as six hexadecimal digits, e.g. "ee0701".
+    pub fn label(&self, name: &str, color: &str) -> Cell {
+        match parse_hex(color) {
+            Some((r, g, b)) if self.color => {
+                // Use black or white text, whichever stands out more against the label's colour.
+                let foreground = if r as u32 * 299 + g as u32 * 587 + b as u32 * 114 > 150_000 {
+                    "30"
+                } else {
+                    "97"
+                };
+
+                Cell {
+                    text: format!("\x1b[{};48;2;{};{};{}m {} \x1b[0m", foreground, r, g, b, name),
+                    width: width(name) + 2,
+                }
+            }
+            _ => Cell::from(name),
+        }
+    }
+}
+
+/// Some text, along with how wide it'll appear on the terminal.
+#[derive(Clone, Debug)]
+pub struct Cell {
+    text: String,
+    width: usize,
+}
+
+impl<'a> From<&'a str> for Cell {
+    fn from(text: &'a str) -> Cell {
+        Cell {
+            text: text.to_owned(),
+            width: width(text),
+        }
+    }
+}
+
+impl From<String> for Cell {
+    fn from(text: String) -> Cell {
+        Cell {
+            width: width(&text[..]),
+            text,
+        }
+    }
+}
+
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.text)
+    }
+}
+
+/// Rows of cells, printed with each column lined up.
+#[derive(Debug, Default)]
+pub struct Table {
+    rows: Vec<Vec<Cell>>,
+}
+
+impl Table {
+    pub fn new() -> Table {
+        Table::default()
+    }
+
+    pub fn push(&mut self, row: Vec<Cell>) {
+        self.rows.push(row);
+    }
+
+    /// Prints the table to stdout, with two spaces between each column.
+    pub fn print(&self) {
+        let mut widths: Vec<usize> = Vec::new();
+        for row in &self.rows {
+            for (index, cell) in row.iter().enumerate() {
+                if index == widths.len() {
+                    widths.push(0);
+                }
+
+                widths[index] = widths[index].max(cell.width);
+            }
+        }
+
+        for row in &self.rows {
+            let mut line = String::new();
+            for (index, cell) in row.iter().enumerate() {
+                line.push_str(&cell.text[..]);
+
+                // Don't pad the last column, so that lines don't end with spaces.
+                if index + 1 < row.len() {
+                    line.extend(std::iter::repeat_n(' ', widths[index] - cell.width + 2));
+                }
+            }
+
+            println!("{}", line);
+        }
+    }
+}
+
+/// Parses a colour in the form "rrggbb", with or without a leading "#".
+fn parse_hex(color: &str) -> Option<(u8, u8, u8)> {
+    let color = color.trim_start_matches('#');
+    if color.len() != 6 || !color.is_char_boundary(2) || !color.is_char_boundary(4) {
+        return None;
+    }
+
+    let channel = |range: &str| u8::from_str_radix(range, 16).ok();
+    match (channel(&color[0..2]), channel(&color[2..4]), channel(&color[4..6])) {
+        (Some(r), Some(g), Some(b)) => Some((r, g, b)),
+        _ => None,
+    }
+}
+
+/// How wide `text` will appear on the terminal, assuming each character takes up a single column.
+fn width(text: &str) -> usize {
+    text.chars().count()
+}
@@ -0,0 +1,559 @@
+//
+//  tests/label_copy.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+#[macro_use]
+extern crate serde_json;
+
+mod support;
+
+use serde_json::Value;
+
+use std::fs;
+use std::thread;
+
+use support::{ghtool, ghtool_in, scratch_dir, Server};
+
+/// Starts a server with a repository of labels to copy from, and an empty repository to copy them to.
+fn server_with_labels() -> Server {
+    let server = Server::start();
+    {
+        let mut state = server.state();
+        state.add_label("acme/labels", "bug", "ee0701");
+        state.add_label("acme/labels", "enhancement", "84b6eb");
+        state.add_label("acme/labels", "question", "cc317c");
+        state.repo("acme/web");
+    }
+
+    server
+}
+
+#[test]
+fn copies_labels() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(
+        server.state().label_names("acme/web"),
+        vec!["bug", "enhancement", "question"]
+    );
+    assert_eq!(
+        run.lines(),
+        vec![
+            "+ Copied label bug",
+            "+ Copied label enhancement",
+            "+ Copied label question",
+        ]
+    );
+}
+
+#[test]
+fn copies_label_colours() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+
+    let state = server.state();
+    assert_eq!(state.repos["acme/web"].labels, state.repos["acme/labels"].labels);
+}
+
+#[test]
+fn copies_label_descriptions() {
+    let server = server_with_labels();
+    server.state().repo("acme/labels").labels[0].description = Some("Something isn't working".to_owned());
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+
+    let state = server.state();
+    assert_eq!(state.repos["acme/web"].labels[0].description, Some("Something isn't working".to_owned()));
+    assert_eq!(state.repos["acme/web"].labels[1].description, None);
+}
+
+#[test]
+fn copies_labels_to_several_repositories() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+
+    let run = ghtool(
+        &server,
+        &["label", "copy", "--from", "acme/labels", "--to", "acme/web", "acme/api"],
+    );
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web").len(), 3);
+    assert_eq!(server.state().label_names("acme/api").len(), 3);
+    assert!(run.lines().contains(&"+ Copied label bug to acme/api"));
+}
+
+#[test]
+fn shows_the_rate_limit_without_asking_for_it() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+    server.state().rate_limit_headers = false;
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web", "acme/api"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/api").len(), 3);
+    assert!(server.requests().iter().all(|request| request.path != "/rate_limit"));
+}
+
+#[test]
+fn copies_only_the_given_labels() {
+    let server = server_with_labels();
+
+    let run = ghtool(
+        &server,
+        &["label", "copy", "--from", "acme/labels", "--to", "acme/web", "--label", "Question", "-l", "bug"],
+    );
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "question"]);
+}
+
+#[test]
+fn missing_label_exits_with_not_found() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web", "--label", "wontfix"]);
+
+    assert_eq!(run.code, 5);
+    assert!(run.stderr.contains("wontfix"), "stderr: {}", run.stderr);
+    assert!(server.state().label_names("acme/web").is_empty());
+}
+
+const MANIFEST: &str = "[[labels]]\nname = \"bug\"\ncolor = \"ee0701\"\ndescription = \"Something isn't working\"\n\n\
+                        [[labels]]\nname = \"wontfix\"\ncolor = \"ffffff\"\n";
+
+#[test]
+fn copies_labels_from_a_manifest() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+    fs::write(dir.join("labels.toml"), MANIFEST).unwrap();
+
+    let run = ghtool_in(&dir, &server, &["label", "copy", "--manifest", "labels.toml", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "wontfix"]);
+    assert_eq!(
+        server.state().repos["acme/web"].labels[0].description,
+        Some("Something isn't working".to_owned())
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn copies_labels_from_the_default_manifest() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+    fs::write(dir.join("labels.toml"), MANIFEST).unwrap();
+    fs::write(
+        dir.join(".ghtool.toml"),
+        "[defaults]\nrepo = \"acme/web\"\nlabel_manifest = \"labels.toml\"\n",
+    )
+    .unwrap();
+
+    // The manifest is relative to the configuration file, not the working directory.
+    let subdir = dir.join("src");
+    fs::create_dir(&subdir).unwrap();
+
+    let run = ghtool_in(&subdir, &server, &["label", "copy", "--label", "wontfix"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["wontfix"]);
+
+    // --from takes the place of the default manifest.
+    let run = ghtool_in(&subdir, &server, &["label", "copy", "--from", "acme/labels", "--label", "question"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["wontfix", "question"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_manifest_exits_with_usage() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+    fs::write(dir.join("labels.toml"), "[[labels]]\nname = \"bug\"\ncolor = \"red\"\n").unwrap();
+
+    let run = ghtool_in(&dir, &server, &["label", "copy", "--manifest", "labels.toml", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 2);
+    assert!(run.stderr.contains("six hexadecimal digits"), "stderr: {}", run.stderr);
+    assert!(server.state().label_names("acme/web").is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn missing_source_exits_with_usage() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["label", "copy", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 2, "stderr: {}", run.stderr);
+    assert!(server.state().label_names("acme/web").is_empty());
+}
+
+#[test]
+fn completes_the_labels_that_have_been_seen() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 0);
+
+    // The cache is written to a temporary file first, which is renamed into place.
+    let cache_dir = dir.join("cache").join("ghtool");
+    assert!(cache_dir.join("completions.json").exists());
+    assert!(fs::read_dir(&cache_dir)
+        .unwrap()
+        .all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+    let run = ghtool_in(&dir, &server, &["complete-args", "label", "--repo", "acme/labels", "e"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["enhancement"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn copies_labels_in_order_with_several_jobs() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+    server.state().repo("acme/docs");
+
+    let run = ghtool(
+        &server,
+        &["--jobs", "4", "label", "copy", "--from", "acme/labels", "--to", "acme/web", "acme/api", "acme/docs"],
+    );
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    for repo in &["acme/web", "acme/api", "acme/docs"] {
+        // The labels may have been created in any order, but the output is always in the same order.
+        let mut names = server.state().label_names(repo);
+        names.sort();
+        assert_eq!(names, vec!["bug", "enhancement", "question"]);
+    }
+    assert_eq!(
+        run.lines(),
+        vec![
+            "+ Copied label bug to acme/web",
+            "+ Copied label enhancement to acme/web",
+            "+ Copied label question to acme/web",
+            "+ Copied label bug to acme/api",
+            "+ Copied label enhancement to acme/api",
+            "+ Copied label question to acme/api",
+            "+ Copied label bug to acme/docs",
+            "+ Copied label enhancement to acme/docs",
+            "+ Copied label question to acme/docs",
+        ]
+    );
+}
+
+#[test]
+fn existing_labels_are_a_partial_failure() {
+    let server = server_with_labels();
+    server.state().add_label("acme/web", "Bug", "000000");
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 6);
+    assert_eq!(server.state().label_names("acme/web"), vec!["Bug", "enhancement", "question"]);
+    assert!(run.stderr.contains("Failed to copy 1 of 3 labels"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn stops_copying_once_the_rate_limit_is_exhausted() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+    // One request to list the labels, and one to create the first label.
+    server.state().remaining = 2;
+
+    let run = ghtool(
+        &server,
+        &["label", "copy", "--from", "acme/labels", "--to", "acme/web", "acme/api"],
+    );
+
+    assert_eq!(run.code, 7);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug"]);
+    assert!(server.state().label_names("acme/api").is_empty());
+
+    // The response to the first label said that no requests were left, so nothing else should have been attempted.
+    let creates = server
+        .requests()
+        .into_iter()
+        .filter(|request| request.method == "POST")
+        .count();
+    assert_eq!(creates, 1);
+    assert!(run.stderr.contains("--resume"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn resumes_copying_once_the_rate_limit_resets() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+    server.state().remaining = 2;
+
+    let dir = scratch_dir();
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web", "acme/api"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 7);
+
+    // The labels are written to a temporary file first, which is renamed into place.
+    let data_dir = dir.join("data").join("ghtool");
+    assert!(data_dir.join("copy-resume.json").exists());
+    assert!(fs::read_dir(&data_dir)
+        .unwrap()
+        .all(|entry| !entry.unwrap().file_name().to_string_lossy().ends_with(".tmp")));
+
+    server.state().remaining = 5000;
+    let run = ghtool_in(&dir, &server, &["label", "copy", "--resume"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "enhancement", "question"]);
+    assert_eq!(server.state().label_names("acme/api"), vec!["bug", "enhancement", "question"]);
+
+    // Once everything has been copied, there's nothing left to resume.
+    assert_eq!(ghtool_in(&dir, &server, &["label", "copy", "--resume"]).code, 5);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn keeps_labels_left_over_from_a_different_copy() {
+    let server = server_with_labels();
+    server.state().repo("acme/api");
+    server.state().remaining = 2;
+
+    let dir = scratch_dir();
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 7);
+
+    // A second copy that's stopped by the rate limit too mustn't forget the labels left over from the first.
+    server.state().remaining = 2;
+    let run = ghtool_in(&dir, &server, &["label", "copy", "--from", "acme/labels", "--to", "acme/api"]);
+    assert_eq!(run.code, 7);
+    assert!(run.stderr.contains("already waiting to be copied"), "stderr: {}", run.stderr);
+
+    server.state().remaining = 5000;
+    let run = ghtool_in(&dir, &server, &["label", "copy", "--resume"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "enhancement", "question"]);
+    // Only the labels left over from the first copy are copied when resuming.
+    assert_ne!(server.state().label_names("acme/api"), vec!["bug", "enhancement", "question"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn retries_creating_labels_after_server_errors() {
+    let server = server_with_labels();
+    server.state().fail_times("POST", "/repos/acme/web/labels", 500, "Server Error", 1);
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "enhancement", "question"]);
+}
+
+#[test]
+fn does_not_create_labels_twice_when_a_response_is_lost() {
+    let server = server_with_labels();
+    // The first label is created, but ghtool is told that it wasn't.
+    server.state().fail_after("POST", "/repos/acme/web/labels", 502, "Bad Gateway", 1);
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/labels", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug", "enhancement", "question"]);
+
+    // Rather than creating the first label again, ghtool should have found that it already existed.
+    let requests = server.requests();
+    assert_eq!(requests.iter().filter(|request| request.method == "POST").count(), 3);
+    assert!(requests.iter().any(|request| request.path == "/repos/acme/web/labels/bug"));
+}
+
+#[test]
+fn reports_copy_results_as_json() {
+    let server = server_with_labels();
+    server.state().add_label("acme/web", "bug", "ee0701");
+
+    let run = ghtool(
+        &server,
+        &["-o", "json", "label", "copy", "--from", "acme/labels", "--to", "acme/web"],
+    );
+
+    assert_eq!(run.code, 6);
+
+    let output: Value = serde_json::from_str(&run.stdout[..]).unwrap();
+    assert_eq!(output["from"], json!("acme/labels"));
+
+    let results = output["results"].as_array().unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0]["label"], json!("bug"));
+    assert_eq!(results[0]["copied"], json!(false));
+    assert!(results[0]["error"].as_str().unwrap().contains("422"));
+    assert_eq!(results[1]["copied"], json!(true));
+}
+
+#[test]
+fn missing_source_repository_exits_with_not_found() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["label", "copy", "--from", "acme/missing", "--to", "acme/web"]);
+
+    assert_eq!(run.code, 5);
+    assert!(server.state().label_names("acme/web").is_empty());
+}
+
+#[test]
+fn records_copied_labels_in_the_journal() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 0);
+
+    let run = ghtool_in(&dir, &server, &["-o", "json", "history"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+
+    let output: Value = serde_json::from_str(&run.stdout[..]).unwrap();
+    assert_eq!(output["run"], json!(1));
+    assert_eq!(output["command"], json!("label copy"));
+    assert_eq!(output["changes"], json!(3));
+    assert_eq!(output["repos"], json!(["acme/web"]));
+
+    let run = ghtool_in(&dir, &server, &["history", "1"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(
+        &run.lines()[1..],
+        &[
+            "+ Created label bug in acme/web",
+            "+ Created label enhancement in acme/web",
+            "+ Created label question in acme/web",
+        ]
+    );
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn runs_at_the_same_time_are_given_different_numbers() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+    let targets = ["acme/api", "acme/web", "acme/docs", "acme/worker"];
+    for target in &targets {
+        server.state().repo(target);
+    }
+
+    thread::scope(|scope| {
+        for target in &targets {
+            let (dir, server) = (&dir, &server);
+            scope.spawn(move || {
+                let run = ghtool_in(dir, server, &["label", "copy", "--from", "acme/labels", "--to", target]);
+                assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+            });
+        }
+    });
+
+    let run = ghtool_in(&dir, &server, &["-o", "json", "history"]);
+    let runs = run.lines().iter().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<Value>>();
+
+    let ids = runs.iter().map(|run| run["run"].clone()).collect::<Vec<_>>();
+    assert_eq!(ids, vec![json!(1), json!(2), json!(3), json!(4)]);
+    assert!(runs.iter().all(|run| run["changes"] == json!(3) && run["repos"].as_array().unwrap().len() == 1));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undo_deletes_copied_labels() {
+    let server = server_with_labels();
+    server.state().add_label("acme/web", "wontfix", "ffffff");
+    let dir = scratch_dir();
+
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 0);
+
+    let run = ghtool_in(&dir, &server, &["undo", "1"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["wontfix"]);
+    assert_eq!(
+        run.lines(),
+        vec![
+            "- Deleted label question from acme/web",
+            "- Deleted label enhancement from acme/web",
+            "- Deleted label bug from acme/web",
+        ]
+    );
+
+    // The undo is recorded as well, so it can be undone in turn.
+    let history = ghtool_in(&dir, &server, &["-o", "json", "history"]);
+    let runs = history.lines().iter().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<Value>>();
+    assert_eq!(runs[0]["undone_by"], json!([2]));
+    assert_eq!(runs[1]["undoes"], json!(1));
+
+    assert_eq!(ghtool_in(&dir, &server, &["undo", "2"]).code, 0);
+    assert_eq!(server.state().label_names("acme/web"), vec!["wontfix", "bug", "enhancement", "question"]);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undo_leaves_labels_that_have_been_edited_alone() {
+    let server = server_with_labels();
+    let dir = scratch_dir();
+
+    let args = ["label", "copy", "--from", "acme/labels", "--to", "acme/web"];
+    assert_eq!(ghtool_in(&dir, &server, &args).code, 0);
+    server.state().repo("acme/web").labels[0].color = "000000".to_owned();
+
+    let run = ghtool_in(&dir, &server, &["undo", "1"]);
+
+    assert_eq!(run.code, 6, "stderr: {}", run.stderr);
+    assert_eq!(server.state().label_names("acme/web"), vec!["bug"]);
+    assert!(run.stderr.contains("edited"), "stderr: {}", run.stderr);
+
+    // Undoing the run again only has the edited label left to undo.
+    let run = ghtool_in(&dir, &server, &["undo", "1"]);
+    assert_eq!(run.code, 6, "stderr: {}", run.stderr);
+    assert!(run.stderr.contains("Failed to undo 1 of 3 changes"), "stderr: {}", run.stderr);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn undo_unknown_run_exits_with_not_found() {
+    let server = server_with_labels();
+
+    let run = ghtool(&server, &["undo", "1"]);
+
+    assert_eq!(run.code, 5);
+    assert!(run.stderr.contains("history"), "stderr: {}", run.stderr);
+}
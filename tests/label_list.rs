@@ -0,0 +1,589 @@
+//
+//  tests/label_list.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+#[macro_use]
+extern crate serde_json;
+
+mod support;
+
+use serde_json::Value;
+
+use std::fs;
+use std::io::ErrorKind;
+use std::net::TcpListener;
+
+use support::{app_key, ghtool, ghtool_as_app, ghtool_in, ghtool_with_env, scratch_dir, Server};
+
+#[test]
+fn lists_labels() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().add_label("acme/web", "enhancement", "84b6eb");
+
+    let run = ghtool(&server, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug", "enhancement"]);
+}
+
+#[test]
+fn lists_every_page_of_labels() {
+    let server = Server::start();
+    server.state().page_size = 10;
+    for number in 0..25 {
+        server.state().add_label("acme/web", &format!("label-{:02}", number), "ededed");
+    }
+
+    let run = ghtool(&server, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines().len(), 25);
+    assert_eq!(run.lines()[24], "label-24");
+}
+
+#[test]
+fn lists_labels_as_json() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().add_label("acme/web", "question", "cc317c");
+    server.state().add_label("acme/web", "wontfix", "ffffff");
+    {
+        let mut state = server.state();
+        let labels = &mut state.repo("acme/web").labels;
+        labels[0].description = Some("Something isn't working".to_owned());
+        labels[2].description = Some(String::new());
+    }
+
+    let run = ghtool(&server, &["--output", "json", "label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    let labels = run.lines().iter().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<Value>>();
+    assert_eq!(
+        labels,
+        vec![
+            json!({ "repo": "acme/web", "name": "bug", "color": "ee0701", "description": "Something isn't working" }),
+            json!({ "repo": "acme/web", "name": "question", "color": "cc317c" }),
+            json!({ "repo": "acme/web", "name": "wontfix", "color": "ffffff" }),
+        ]
+    );
+}
+
+#[test]
+fn lists_labels_in_every_repository_of_an_owner() {
+    let server = Server::start();
+    {
+        let mut state = server.state();
+        state.orgs.insert("acme".to_owned());
+        state.add_label("acme/api", "bug", "ee0701");
+        state.add_label("acme/web", "design", "c5def5");
+        state.add_label("acme/old", "wontfix", "ffffff");
+        state.repo("acme/old").archived = true;
+        state.add_label("other/web", "other", "000000");
+    }
+
+    let run = ghtool(&server, &["label", "list", "acme/*"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["acme/api: bug", "acme/web: design"]);
+}
+
+#[test]
+fn lists_labels_in_repositories_with_a_topic() {
+    let server = Server::start();
+    {
+        let mut state = server.state();
+        state.add_label("acme/api", "bug", "ee0701");
+        state.repo("acme/api").topics.push("backend".to_owned());
+        state.add_label("acme/web", "design", "c5def5");
+        state.add_label("acme/worker", "queue", "fbca04");
+        state.repo("acme/worker").topics.push("backend".to_owned());
+    }
+
+    let run = ghtool(&server, &["label", "list", "@topic:acme/backend"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["acme/api: bug", "acme/worker: queue"]);
+}
+
+#[test]
+fn lists_labels_in_order_with_several_jobs() {
+    let server = Server::start();
+    {
+        let mut state = server.state();
+        state.orgs.insert("acme".to_owned());
+        for repo in &["acme/a", "acme/b", "acme/c", "acme/d", "acme/e", "acme/f"] {
+            state.add_label(repo, "bug", "ee0701");
+            state.add_label(repo, "question", "cc317c");
+        }
+    }
+
+    let run = ghtool(&server, &["--jobs", "4", "label", "list", "acme/*"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(
+        run.lines(),
+        vec![
+            "acme/a: bug",
+            "acme/a: question",
+            "acme/b: bug",
+            "acme/b: question",
+            "acme/c: bug",
+            "acme/c: question",
+            "acme/d: bug",
+            "acme/d: question",
+            "acme/e: bug",
+            "acme/e: question",
+            "acme/f: bug",
+            "acme/f: question",
+        ]
+    );
+}
+
+#[test]
+fn lists_labels_in_many_repositories_with_graphql() {
+    let server = Server::start();
+    server.state().page_size = 2;
+    server.state().add_label("acme/api", "bug", "ee0701");
+    for name in &["design", "docs", "perf"] {
+        server.state().add_label("acme/web", name, "c5def5");
+    }
+
+    let run = ghtool(&server, &["label", "list", "acme/web", "acme/api"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["acme/web: design", "acme/web: docs", "acme/web: perf", "acme/api: bug"]);
+
+    // One query for both repositories, and another for the rest of acme/web's labels.
+    let requests = server.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests.iter().all(|request| request.method == "POST" && request.path == "/graphql"));
+}
+
+#[test]
+fn lists_labels_with_rest_if_graphql_is_unavailable() {
+    let server = Server::start();
+    server.state().add_label("acme/api", "bug", "ee0701");
+    server.state().add_label("acme/web", "design", "c5def5");
+    server.state().fail("POST", "/graphql", 404, "Not Found");
+
+    let run = ghtool(&server, &["label", "list", "acme/web", "acme/api"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["acme/web: design", "acme/api: bug"]);
+}
+
+#[test]
+fn lists_labels_around_a_missing_repository_with_graphql() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "design", "c5def5");
+    server.state().add_label("acme/api", "bug", "ee0701");
+
+    let run = ghtool(&server, &["label", "list", "acme/web", "acme/missing", "acme/api"]);
+
+    assert_eq!(run.code, 6);
+    assert_eq!(run.lines(), vec!["acme/web: design", "acme/api: bug"]);
+    assert!(run.stderr.contains("acme/missing"), "stderr: {}", run.stderr);
+    assert!(run.stderr.contains("Failed to list the labels in 1 of 3 repositories"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn reports_repositories_that_cant_be_listed_as_json() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "design", "c5def5");
+    server.state().add_label("acme/api", "bug", "ee0701");
+
+    let run = ghtool(&server, &["-o", "json", "--jobs", "1", "label", "list", "acme/missing", "acme/web", "acme/api"]);
+    assert_eq!(run.code, 6);
+
+    let objects = run.lines().iter().map(|line| serde_json::from_str(line).unwrap()).collect::<Vec<Value>>();
+    assert_eq!(objects.len(), 3);
+    assert_eq!(objects[0]["repo"], json!("acme/missing"));
+    assert!(objects[0]["error"].as_str().unwrap().contains("acme/missing"), "{}", objects[0]);
+    assert_eq!(objects[1]["name"], json!("design"));
+    assert_eq!(objects[2]["name"], json!("bug"));
+}
+
+#[test]
+fn counts_label_usage() {
+    let server = Server::start();
+    {
+        let mut state = server.state();
+        state.add_label("acme/web", "bug", "ee0701");
+        state.add_label("acme/web", "wontfix", "ffffff");
+        state.add_label("acme/api", "bug", "ee0701");
+        state.add_issue("acme/web", "Broken", &["bug"]);
+        state.add_issue("acme/web", "Also broken", &["bug"]);
+        state.add_issue("acme/api", "Slow", &["bug"]);
+    }
+
+    // A single repository is listed with the REST API.
+    let run = ghtool(&server, &["label", "list", "--usage", "acme/web"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug\t2", "wontfix\t0"]);
+
+    // Several are listed with the GraphQL API.
+    let run = ghtool(&server, &["--output", "json", "label", "list", "--usage", "acme/web", "acme/api"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    let issues = run
+        .lines()
+        .iter()
+        .map(|line| serde_json::from_str::<Value>(line).unwrap()["issues"].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(issues, vec![json!(2), json!(0), json!(1)]);
+}
+
+#[test]
+fn lists_labels_again_from_the_cache() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    let dir = scratch_dir();
+
+    let first = ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    assert_eq!(first.code, 0, "stderr: {}", first.stderr);
+    let remaining = server.state().remaining;
+
+    // Nothing has changed, so GitHub responds with 304 Not Modified, which doesn't count towards the rate limit.
+    let second = ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    assert_eq!(second.code, 0, "stderr: {}", second.stderr);
+    assert_eq!(second.lines(), vec!["bug"]);
+    assert_eq!(server.state().remaining, remaining);
+    assert!(server.requests().last().unwrap().headers.contains_key("if-none-match"));
+
+    server.state().add_label("acme/web", "design", "c5def5");
+    let third = ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    assert_eq!(third.code, 0, "stderr: {}", third.stderr);
+    assert_eq!(third.lines(), vec!["bug", "design"]);
+
+    // Responses are written to temporary files first, none of which should be left behind.
+    let cached = fs::read_dir(dir.join("cache").join("ghtool").join("http"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<_>>();
+    assert!(!cached.is_empty());
+    assert!(cached.iter().all(|path| path.extension().unwrap() == "json"), "{:?}", cached);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn no_cache_sends_every_request_in_full() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    let dir = scratch_dir();
+
+    ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    let run = ghtool_in(&dir, &server, &["--no-cache", "label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug"]);
+    assert!(!server.requests().last().unwrap().headers.contains_key("if-none-match"));
+    assert_eq!(server.state().remaining, 4998);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn cache_clear_removes_cached_responses() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().add_label("acme/api", "bug", "ee0701");
+    let dir = scratch_dir();
+
+    ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    ghtool_in(&dir, &server, &["label", "list", "acme/api"]);
+
+    let run = ghtool_in(&dir, &server, &["--output", "json", "cache", "clear"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    let output: Value = serde_json::from_str(&run.stdout).unwrap();
+    assert_eq!(output["removed"], json!(2));
+
+    ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    assert!(!server.requests().last().unwrap().headers.contains_key("if-none-match"));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn sends_requests_through_a_proxy() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    let dir = scratch_dir();
+
+    // The API can only be reached through the proxy, since its host doesn't exist.
+    let vars = [("GHTOOL_API_URL", "http://api.github.invalid"), ("HTTP_PROXY", server.url())];
+    let run = ghtool_with_env(&dir, &server, &vars, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug"]);
+    assert!(server.requests().iter().all(|request| request.proxied));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn bypasses_the_proxy_for_hosts_in_no_proxy() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    let dir = scratch_dir();
+
+    // Nothing listens on port 9, so the request would fail if it were sent through the proxy.
+    let vars = [("HTTP_PROXY", "http://127.0.0.1:9"), ("NO_PROXY", "127.0.0.1")];
+    let run = ghtool_with_env(&dir, &server, &vars, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert!(server.requests().iter().all(|request| !request.proxied));
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_ca_bundle_exits_with_config() {
+    let server = Server::start();
+    let dir = scratch_dir();
+    fs::write(dir.join("proxy.pem"), "not a certificate").unwrap();
+
+    let run = ghtool_in(&dir, &server, &["--cacert", "proxy.pem", "label", "list", "acme/web"]);
+    assert_eq!(run.code, 3);
+    assert!(run.stderr.contains("proxy.pem"), "stderr: {}", run.stderr);
+
+    let run = ghtool_in(&dir, &server, &["--cacert", "missing.pem", "label", "list", "acme/web"]);
+    assert_eq!(run.code, 3);
+    assert!(server.requests().is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn project_configuration_can_only_set_defaults() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+
+    let dir = scratch_dir();
+    fs::write(
+        dir.join(".ghtool.toml"),
+        "ca_bundle = \"missing.pem\"\n\n[defaults]\nrepo = \"acme/web\"\n",
+    )
+    .unwrap();
+
+    let run = ghtool_in(&dir, &server, &["label", "list"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug"]);
+    assert!(run.stderr.contains("Ignoring \"ca_bundle\""), "stderr: {}", run.stderr);
+
+    let run = ghtool_in(&dir, &server, &["config", "validate"]);
+    assert_eq!(run.code, 3);
+    assert!(run.stdout.contains("can't be set in a project-local"), "stdout: {}", run.stdout);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn never_sends_credentials_to_hosts_from_project_configuration() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+
+    // Stands in for a server run by whoever controls the project-local file, which records anything sent to it.
+    let foreign = TcpListener::bind("127.0.0.1:0").unwrap();
+    foreign.set_nonblocking(true).unwrap();
+    let foreign_repo = format!("https://{}/acme/web", foreign.local_addr().unwrap());
+
+    let dir = scratch_dir();
+    fs::write(
+        dir.join(".ghtool.toml"),
+        format!("[defaults]\nrepo = \"{0}\"\n\n[groups]\nforeign = [\"{0}\"]\n", foreign_repo),
+    )
+    .unwrap();
+
+    let run = ghtool_in(&dir, &server, &["label", "list"]);
+    assert_eq!(run.code, 3, "stderr: {}", run.stderr);
+    assert!(run.stderr.contains("isn't a trusted GitHub instance"), "stderr: {}", run.stderr);
+
+    let run = ghtool_in(&dir, &server, &["label", "list", ":foreign", "acme/web"]);
+    assert_eq!(run.code, 6, "stderr: {}", run.stderr);
+    assert!(run.stdout.contains("bug"), "stdout: {}", run.stdout);
+
+    match foreign.accept() {
+        Err(ref err) if err.kind() == ErrorKind::WouldBlock => (),
+        result => panic!("ghtool connected to the foreign host: {:?}", result),
+    }
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn sets_github_app_details_one_at_a_time() {
+    let server = Server::start();
+    let dir = scratch_dir();
+    let key = app_key();
+
+    let run = ghtool_in(&dir, &server, &["config", "set", "app.app_id", "123"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+
+    // The [app] table is incomplete until every key has been set, which is only a problem once it's used.
+    let run = ghtool_in(&dir, &server, &["config", "validate"]);
+    assert_eq!(run.code, 3);
+    assert!(run.stdout.contains("\"app.private_key_path\""), "stdout: {}", run.stdout);
+
+    let run = ghtool_in(&dir, &server, &["config", "set", "app.private_key_path", key.to_str().unwrap()]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    let run = ghtool_in(&dir, &server, &["config", "set", "app.installation_id", "42"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+
+    let run = ghtool_in(&dir, &server, &["config", "get", "app.installation_id"]);
+    assert_eq!(run.lines(), vec!["42"]);
+    let run = ghtool_in(&dir, &server, &["config", "validate"]);
+    assert_eq!(run.code, 0, "stdout: {}", run.stdout);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn authenticates_as_a_github_app_installation() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().add_label("acme/api", "design", "c5def5");
+
+    let dir = scratch_dir();
+    let run = ghtool_as_app(&dir, &server, &app_key(), &["--jobs", "1", "label", "list", "acme/web", "acme/api"]);
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["acme/web: bug", "acme/api: design"]);
+
+    // The installation access token is requested from the same API as everything else, and only once.
+    let requests = server.requests();
+    let token_requests = requests.iter().filter(|request| request.path.starts_with("/app/")).collect::<Vec<_>>();
+    assert_eq!(token_requests.len(), 1);
+    assert_eq!(token_requests[0].path, "/app/installations/42/access_tokens");
+    assert_eq!(requests[0].path, token_requests[0].path);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn invalid_app_private_key_exits_with_auth() {
+    let server = Server::start();
+    let dir = scratch_dir();
+    fs::write(dir.join("key.pem"), "not a key").unwrap();
+
+    let run = ghtool_as_app(&dir, &server, &dir.join("key.pem"), &["label", "list", "acme/web"]);
+    assert_eq!(run.code, 4, "stderr: {}", run.stderr);
+    assert!(server.requests().is_empty());
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn missing_repository_exits_with_not_found() {
+    let server = Server::start();
+
+    let run = ghtool(&server, &["label", "list", "acme/missing"]);
+
+    assert_eq!(run.code, 5);
+    assert!(run.stderr.contains("404"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn rejected_credentials_exit_with_auth() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().fail("GET", "/repos/acme/web/labels", 401, "Bad credentials");
+
+    let run = ghtool(&server, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 4);
+    assert!(run.stderr.contains("Bad credentials"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn exhausted_rate_limit_exits_with_rate_limited() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().remaining = 0;
+
+    let run = ghtool(&server, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 7);
+    assert!(run.stderr.contains("rate limit"), "stderr: {}", run.stderr);
+}
+
+#[test]
+fn rate_limited_query_exits_with_rate_limited() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().add_label("acme/api", "bug", "ee0701");
+    server.state().graphql_rate_limited = true;
+
+    // The labels of more than one repository are fetched with a GraphQL query.
+    let dir = scratch_dir();
+    let args = ["--output", "json", "label", "list", "acme/web", "acme/api"];
+    let run = ghtool_with_env(&dir, &server, &[("TZ", "UTC")], &args);
+
+    assert_eq!(run.code, 7, "stderr: {}", run.stderr);
+
+    // The reset time comes from the response's headers, and is too far away to wait for, so the query isn't sent again.
+    let error: Value = serde_json::from_str(run.stderr.lines().last().unwrap()).unwrap();
+    assert!(error["error"].as_str().unwrap().contains("2100-01-01"), "stderr: {}", run.stderr);
+    assert_eq!(error["status"], Value::Null);
+    assert_eq!(server.requests().iter().filter(|request| request.path == "/graphql").count(), 1);
+
+    let _ = fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn retries_after_server_errors() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().fail_times("GET", "/repos/acme/web/labels", 502, "Server Error", 2);
+
+    let run = ghtool(&server, &["label", "list", "acme/web"]);
+
+    assert_eq!(run.code, 0, "stderr: {}", run.stderr);
+    assert_eq!(run.lines(), vec!["bug"]);
+    assert_eq!(server.requests().len(), 3);
+}
+
+#[test]
+fn gives_up_after_the_configured_number_of_attempts() {
+    let server = Server::start();
+    server.state().add_label("acme/web", "bug", "ee0701");
+    server.state().fail("GET", "/repos/acme/web/labels", 503, "Service Unavailable");
+
+    let dir = scratch_dir();
+    fs::write(dir.join("config.toml"), "[retry]\nmax_attempts = 2\ninitial_delay_ms = 0\n").unwrap();
+    let run = ghtool_in(&dir, &server, &["label", "list", "acme/web"]);
+    let _ = fs::remove_dir_all(&dir);
+
+    assert_eq!(run.code, 1);
+    assert!(run.stderr.contains("503"), "stderr: {}", run.stderr);
+    assert_eq!(server.requests().len(), 2);
+}
+
+#[test]
+fn errors_are_reported_as_json() {
+    let server = Server::start();
+
+    let run = ghtool(&server, &["--output", "json", "label", "list", "acme/missing"]);
+
+    assert_eq!(run.code, 5);
+    assert!(run.stdout.is_empty());
+
+    let error: Value = serde_json::from_str(run.stderr.lines().last().unwrap()).unwrap();
+    assert_eq!(error["status"], json!(404));
+    assert_eq!(error["exit_code"], json!(5));
+}
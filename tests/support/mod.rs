@@ -0,0 +1,163 @@
+//
+//  tests/support/mod.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! Support for the integration tests, which run the real `ghtool` binary against a fake GitHub API server.
+
+// Each test file uses a different part of this module.
+#![allow(dead_code)]
+
+pub mod server;
+
+pub use self::server::Server;
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// The number of the next scratch directory, which keeps tests running in parallel from sharing directories.
+static NEXT_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// The environment variables that ghtool reads proxy settings from.
+const PROXY_VARS: [&str; 8] = [
+    "HTTPS_PROXY",
+    "https_proxy",
+    "HTTP_PROXY",
+    "http_proxy",
+    "ALL_PROXY",
+    "all_proxy",
+    "NO_PROXY",
+    "no_proxy",
+];
+
+/// The result of running ghtool.
+pub struct Run {
+    pub code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl Run {
+    /// The lines printed to stdout.
+    pub fn lines(&self) -> Vec<&str> {
+        self.stdout.lines().collect()
+    }
+}
+
+impl From<Output> for Run {
+    fn from(output: Output) -> Run {
+        Run {
+            code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+        }
+    }
+}
+
+/// Returns the path of the `ghtool` binary that Cargo built for these tests.
+fn binary() -> PathBuf {
+    // Integration tests are built into target/<profile>/deps, and the binary into target/<profile>.
+    let mut path = env::current_exe().expect("Unable to find the test executable");
+    path.pop();
+    if path.ends_with("deps") {
+        path.pop();
+    }
+
+    path.join(format!("ghtool{}", env::consts::EXE_SUFFIX))
+}
+
+/// Creates an empty directory for ghtool to run in, so that it doesn't find any configuration files or caches from the
+/// machine running the tests.
+pub fn scratch_dir() -> PathBuf {
+    let dir = env::temp_dir().join(format!(
+        "ghtool-test-{}-{}",
+        ::std::process::id(),
+        NEXT_DIR.fetch_add(1, Ordering::SeqCst)
+    ));
+
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("Unable to create a scratch directory");
+    dir
+}
+
+/// Runs ghtool with `args` against `server`, authenticating with the token that the server accepts.
+pub fn ghtool(server: &Server, args: &[&str]) -> Run {
+    let dir = scratch_dir();
+    let run = ghtool_in(&dir, server, args);
+
+    let _ = fs::remove_dir_all(&dir);
+    run
+}
+
+/// Runs ghtool in `dir` with `args` against `server`, so that several runs can share the same caches.
+pub fn ghtool_in(dir: &Path, server: &Server, args: &[&str]) -> Run {
+    ghtool_with_env(dir, server, &[], args)
+}
+
+/// Runs ghtool in `dir` with `args` against `server`, setting the environment variables in `vars` as well as (or
+/// instead of) the usual ones.
+pub fn ghtool_with_env(dir: &Path, server: &Server, vars: &[(&str, &str)], args: &[&str]) -> Run {
+    let mut token_args = vec!["--token", server::TOKEN];
+    token_args.extend_from_slice(args);
+    ghtool_with_credentials_in(dir, server, vars, &token_args[..])
+}
+
+/// Returns the path to a private key for a GitHub App, which the server accepts tokens signed with.
+pub fn app_key() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests").join("support").join("app-key.pem")
+}
+
+/// Runs ghtool in `dir` with `args` against `server` as a GitHub App installation, with a configuration file whose
+/// `[app]` table has the installation the server accepts and the private key at `key`.
+pub fn ghtool_as_app(dir: &Path, server: &Server, key: &Path, args: &[&str]) -> Run {
+    let config = format!(
+        "[app]\napp_id = 1\nprivate_key_path = {:?}\ninstallation_id = {}\n",
+        key.display().to_string(),
+        server::INSTALLATION_ID
+    );
+    fs::write(dir.join("config.toml"), config).expect("Unable to write the configuration file");
+
+    ghtool_with_credentials_in(dir, server, &[], args)
+}
+
+/// Runs ghtool in `dir` with `args` against `server`, setting the environment variables in `vars`, without giving it
+/// any credentials other than those in the configuration files.
+fn ghtool_with_credentials_in(dir: &Path, server: &Server, vars: &[(&str, &str)], args: &[&str]) -> Run {
+    let mut command = Command::new(binary());
+    command
+        .args(args)
+        .current_dir(dir)
+        .env("GHTOOL_API_URL", server.url())
+        .env("GHTOOL_CONFIG", dir.join("config.toml"))
+        .env("XDG_CONFIG_HOME", dir.join("config"))
+        .env("XDG_CACHE_HOME", dir.join("cache"))
+        .env("XDG_DATA_HOME", dir.join("data"))
+        .env("HOME", dir)
+        .env("NO_COLOR", "1");
+
+    // A proxy on the machine running the tests would get in the way of reaching the fake server.
+    for var in PROXY_VARS.iter() {
+        command.env_remove(var);
+    }
+
+    let output = command.envs(vars.iter().cloned()).output().expect("Unable to run ghtool");
+    Run::from(output)
+}
@@ -0,0 +1,845 @@
+//
+//  tests/support/server.rs
+//  ghtool
+//
+//  Created by Søren Mortensen on 19/10/2026.
+//  Copyright © 2026 Søren Mortensen.
+//
+//  Licensed under the Apache License, Version 2.0 (the "License");
+//  you may not use this file except in compliance with the License.
+//  You may obtain a copy of the License at
+//
+//      http://www.apache.org/licenses/LICENSE-2.0
+//
+//  Unless required by applicable law or agreed to in writing, software
+//  distributed under the License is distributed on an "AS IS" BASIS,
+//  WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+//  See the License for the specific language governing permissions and
+//  limitations under the License.
+//
+
+//! A fake GitHub API server, which runs on a background thread and keeps everything in memory.
+//!
+//! It implements just enough of the API for ghtool: repositories (listed by organisation, user, team or topic), labels
+//! and issues, with GitHub's pagination, error responses, rate-limit headers and conditional requests. It also answers
+//! the GraphQL queries that ghtool sends, recognising them by their shape rather than parsing them.
+
+use serde_json::{self, Value};
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{BTreeMap, BTreeSet};
+use std::hash::{Hash, Hasher};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::str;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::thread;
+
+/// The access token that the server accepts.
+pub const TOKEN: &str = "test-token";
+
+/// The ID of the GitHub App installation that the server gives installation access tokens to, which are the same as
+/// `TOKEN`.
+pub const INSTALLATION_ID: u64 = 42;
+
+/// A label in a repository.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Label {
+    pub name: String,
+    pub color: String,
+    pub description: Option<String>,
+}
+
+/// An issue in a repository.
+#[derive(Clone, Debug)]
+pub struct Issue {
+    pub number: u64,
+    pub title: String,
+    pub labels: Vec<String>,
+}
+
+/// A repository.
+#[derive(Clone, Debug, Default)]
+pub struct Repo {
+    pub labels: Vec<Label>,
+    pub issues: Vec<Issue>,
+    pub topics: Vec<String>,
+    pub archived: bool,
+}
+
+/// A request that the server received.
+#[derive(Clone, Debug)]
+pub struct Request {
+    pub method: String,
+    /// The path of the request, without the query string.
+    pub path: String,
+    pub query: BTreeMap<String, String>,
+    pub headers: BTreeMap<String, String>,
+    pub body: Vec<u8>,
+    /// Whether the request was sent to the server as a proxy, i.e. with an absolute URL rather than just a path.
+    pub proxied: bool,
+}
+
+/// An error response to send instead of the usual response to a request.
+#[derive(Clone, Debug)]
+pub struct Failure {
+    pub status: u16,
+    pub message: String,
+    /// The number of requests to fail, or `None` to fail every request.
+    pub times: Option<usize>,
+    /// Whether to carry out the request before responding with the error, as if the real response had been lost.
+    pub after: bool,
+}
+
+/// Everything the server knows about.
+#[derive(Debug)]
+pub struct State {
+    /// The repositories, keyed by "owner/name".
+    pub repos: BTreeMap<String, Repo>,
+    /// The owners that are organisations rather than users.
+    pub orgs: BTreeSet<String>,
+    /// The repositories that each team has access to, keyed by "org/team".
+    pub teams: BTreeMap<String, Vec<String>>,
+    /// The number of items in each page of a list, unless the request asks for a different number.
+    pub page_size: usize,
+    pub rate_limit: u32,
+    /// The number of requests that can be made before the rate limit is exhausted.
+    pub remaining: u32,
+    /// Whether to send `X-RateLimit-*` headers, which GitHub Enterprise instances with rate limiting turned off don't.
+    pub rate_limit_headers: bool,
+    /// Whether to respond to GraphQL queries with a RATE_LIMITED error, as GitHub does once the GraphQL API's rate
+    /// limit has been exhausted.
+    pub graphql_rate_limited: bool,
+    /// Responses to send instead of the usual ones, keyed by method and path, e.g. `("POST", "/repos/acme/web/labels")`.
+    pub failures: BTreeMap<(String, String), Failure>,
+    /// Every request the server has received, in order.
+    pub requests: Vec<Request>,
+}
+
+impl Default for State {
+    fn default() -> State {
+        State {
+            repos: BTreeMap::new(),
+            orgs: BTreeSet::new(),
+            teams: BTreeMap::new(),
+            page_size: 30,
+            rate_limit: 5000,
+            remaining: 5000,
+            rate_limit_headers: true,
+            graphql_rate_limited: false,
+            failures: BTreeMap::new(),
+            requests: Vec::new(),
+        }
+    }
+}
+
+impl State {
+    /// Returns the repository `name`, creating it if it doesn't exist.
+    pub fn repo(&mut self, name: &str) -> &mut Repo {
+        self.repos.entry(name.to_owned()).or_default()
+    }
+
+    /// Adds a label to the repository `repo`, creating the repository if it doesn't exist.
+    pub fn add_label(&mut self, repo: &str, name: &str, color: &str) {
+        self.repo(repo).labels.push(Label {
+            name: name.to_owned(),
+            color: color.to_owned(),
+            description: None,
+        });
+    }
+
+    /// Adds an issue with the labels named `labels` to the repository `repo`, creating the repository if it doesn't
+    /// exist.
+    pub fn add_issue(&mut self, repo: &str, title: &str, labels: &[&str]) {
+        let repo = self.repo(repo);
+        let number = repo.issues.len() as u64 + 1;
+        repo.issues.push(Issue {
+            number,
+            title: title.to_owned(),
+            labels: labels.iter().map(|&label| label.to_owned()).collect(),
+        });
+    }
+
+    /// Returns the names of the labels in the repository `repo`.
+    pub fn label_names(&self, repo: &str) -> Vec<String> {
+        self.repos
+            .get(repo)
+            .map(|repo| repo.labels.iter().map(|label| label.name.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Makes the server respond to `method` requests to `path` with `status` and an error `message`.
+    pub fn fail(&mut self, method: &str, path: &str, status: u16, message: &str) {
+        self.add_failure(method, path, status, message, None, false);
+    }
+
+    /// Makes the server respond to the next `times` `method` requests to `path` with `status` and an error `message`.
+    pub fn fail_times(&mut self, method: &str, path: &str, status: u16, message: &str, times: usize) {
+        self.add_failure(method, path, status, message, Some(times), false);
+    }
+
+    /// Makes the server carry out the next `times` `method` requests to `path`, but respond to them with `status` and
+    /// an error `message`, as if the real responses had been lost.
+    pub fn fail_after(&mut self, method: &str, path: &str, status: u16, message: &str, times: usize) {
+        self.add_failure(method, path, status, message, Some(times), true);
+    }
+
+    fn add_failure(&mut self, method: &str, path: &str, status: u16, message: &str, times: Option<usize>, after: bool) {
+        let failure = Failure {
+            status,
+            message: message.to_owned(),
+            times,
+            after,
+        };
+
+        self.failures.insert((method.to_owned(), path.to_owned()), failure);
+    }
+
+    /// Returns the failure to respond to `request` with, if there is one, counting it towards the number of times it
+    /// should happen.
+    fn take_failure(&mut self, request: &Request) -> Option<Failure> {
+        let key = (request.method.clone(), request.path.clone());
+        let failure = self.failures.get(&key)?.clone();
+
+        match failure.times {
+            Some(0) => return None,
+            Some(times) => self.failures.get_mut(&key)?.times = Some(times - 1),
+            None => (),
+        }
+
+        Some(failure)
+    }
+}
+
+/// A fake GitHub API server.
+pub struct Server {
+    url: String,
+    state: Arc<Mutex<State>>,
+}
+
+impl Server {
+    /// Starts a server on a free port on localhost.
+    pub fn start() -> Server {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Unable to bind the fake GitHub server");
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        let state = Arc::new(Mutex::new(State::default()));
+
+        let thread_state = state.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let state = thread_state.clone();
+                thread::spawn(move || handle_connection(stream, &state));
+            }
+        });
+
+        Server { url, state }
+    }
+
+    /// The URL of the server's API, to be given to ghtool in `GHTOOL_API_URL`.
+    pub fn url(&self) -> &str {
+        &self.url[..]
+    }
+
+    /// Locks the server's state, to set it up before running ghtool or to check it afterwards.
+    pub fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap()
+    }
+
+    /// Returns every request the server has received.
+    pub fn requests(&self) -> Vec<Request> {
+        self.state().requests.clone()
+    }
+}
+
+/// A response to a request.
+struct Response {
+    status: u16,
+    body: Value,
+    /// The value of the `Link` header, if the response is a page of a list with more pages after it.
+    link: Option<String>,
+    etag: Option<String>,
+}
+
+impl Response {
+    fn json(status: u16, body: Value) -> Response {
+        Response {
+            status,
+            body,
+            link: None,
+            etag: None,
+        }
+    }
+
+    fn error(status: u16, message: &str) -> Response {
+        Response::json(
+            status,
+            json!({
+                "message": message,
+                "documentation_url": "https://developer.github.com/v3",
+            }),
+        )
+    }
+
+    fn not_found() -> Response {
+        Response::error(404, "Not Found")
+    }
+}
+
+fn handle_connection(stream: TcpStream, state: &Mutex<State>) {
+    let mut reader = BufReader::new(stream.try_clone().expect("Unable to clone the connection"));
+    let mut stream = stream;
+
+    // Keep handling requests until the client closes the connection.
+    while let Some(request) = read_request(&mut reader) {
+        let (response, rate_limit) = {
+            let mut state = state.lock().unwrap();
+            state.requests.push(request.clone());
+            let response = respond(&mut state, &request);
+            let rate_limit = if state.rate_limit_headers {
+                format!(
+                    "X-RateLimit-Limit: {}\r\nX-RateLimit-Remaining: {}\r\nX-RateLimit-Reset: 4102444800\r\n",
+                    state.rate_limit, state.remaining
+                )
+            } else {
+                String::new()
+            };
+            (response, rate_limit)
+        };
+
+        let body = match response.status {
+            204 | 304 => Vec::new(),
+            _ => serde_json::to_vec(&response.body).unwrap(),
+        };
+        let mut head = format!(
+            "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\n{}",
+            response.status,
+            reason(response.status),
+            body.len(),
+            rate_limit
+        );
+
+        if let Some(link) = response.link {
+            head.push_str(&format!("Link: {}\r\n", link));
+        }
+
+        if let Some(etag) = response.etag {
+            head.push_str(&format!("ETag: {}\r\n", etag));
+        }
+
+        head.push_str("\r\n");
+
+        if stream.write_all(head.as_bytes()).and_then(|_| stream.write_all(&body[..])).is_err() {
+            return;
+        }
+    }
+}
+
+/// Reads a request from `reader`, returning `None` once the connection is closed.
+fn read_request<R: BufRead>(reader: &mut R) -> Option<Request> {
+    let mut line = String::new();
+    if reader.read_line(&mut line).ok()? == 0 {
+        return None;
+    }
+
+    let mut parts = line.trim().split(' ');
+    let method = parts.next()?.to_owned();
+    let target = parts.next()?.to_owned();
+
+    let mut headers = BTreeMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).ok()?;
+        let line = line.trim();
+        if line.is_empty() {
+            break;
+        }
+
+        if let Some(colon) = line.find(':') {
+            headers.insert(line[..colon].trim().to_lowercase(), line[colon + 1..].trim().to_owned());
+        }
+    }
+
+    let length = headers.get("content-length").and_then(|length| length.parse().ok()).unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body[..]).ok()?;
+
+    // Requests sent through a proxy name the whole URL, so strip the scheme and host to leave the path.
+    let proxied = target.starts_with("http://");
+    let target = match target.strip_prefix("http://") {
+        Some(rest) => rest.find('/').map(|slash| rest[slash..].to_owned()).unwrap_or_else(|| "/".to_owned()),
+        None => target,
+    };
+
+    let (path, query) = match target.find('?') {
+        Some(question) => (target[..question].to_owned(), parse_query(&target[question + 1..])),
+        None => (target, BTreeMap::new()),
+    };
+
+    Some(Request {
+        method,
+        path,
+        query,
+        headers,
+        body,
+        proxied,
+    })
+}
+
+fn parse_query(query: &str) -> BTreeMap<String, String> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| match pair.find('=') {
+            Some(equals) => (pair[..equals].to_owned(), pair[equals + 1..].to_owned()),
+            None => (pair.to_owned(), String::new()),
+        })
+        .collect()
+}
+
+fn respond(state: &mut State, request: &Request) -> Response {
+    // GitHub Apps authenticate with a JSON Web Token to get an installation access token. The token's signature isn't
+    // checked, just that it has the three parts of a signed token.
+    if request.path == format!("/app/installations/{}/access_tokens", INSTALLATION_ID) {
+        let jwt = request.headers.get("authorization").and_then(|value| value.strip_prefix("Bearer "));
+        return match jwt {
+            Some(jwt) if request.method == "POST" && jwt.split('.').filter(|part| !part.is_empty()).count() == 3 => {
+                Response::json(201, json!({ "token": TOKEN, "expires_at": "2100-01-01T00:00:00Z" }))
+            }
+            _ => Response::error(401, "A JSON web token could not be decoded"),
+        };
+    }
+
+    let authorized = request
+        .headers
+        .get("authorization")
+        .is_some_and(|value| value == &format!("token {}", TOKEN));
+    if !authorized {
+        return Response::error(401, "Bad credentials");
+    }
+
+    let mut response = match state.take_failure(request) {
+        Some(ref failure) if !failure.after => Response::error(failure.status, &failure.message[..]),
+        Some(ref failure) => {
+            route(state, request);
+            Response::error(failure.status, &failure.message[..])
+        }
+        None => route(state, request),
+    };
+
+    // Like GitHub, respond to conditional requests for something that hasn't changed with 304 Not Modified, which
+    // doesn't count towards the rate limit.
+    if request.method == "GET" && request.path != "/rate_limit" && response.status == 200 {
+        let etag = etag(&response);
+        if request.headers.get("if-none-match") == Some(&etag) {
+            state.remaining += 1;
+            return Response {
+                status: 304,
+                body: Value::Null,
+                link: None,
+                etag: Some(etag),
+            };
+        }
+
+        response.etag = Some(etag);
+    }
+
+    response
+}
+
+/// Returns an ETag for `response`, which only changes when the response does.
+fn etag(response: &Response) -> String {
+    let mut hasher = DefaultHasher::new();
+    response.body.to_string().hash(&mut hasher);
+    response.link.hash(&mut hasher);
+    format!("\"{:016x}\"", hasher.finish())
+}
+
+fn route(state: &mut State, request: &Request) -> Response {
+    // Requests for the rate limit don't count towards it.
+    if request.path == "/rate_limit" {
+        let rate = json!({ "limit": state.rate_limit, "remaining": state.remaining, "reset": 4_102_444_800u64 });
+        return Response::json(200, json!({ "resources": { "core": rate.clone() }, "rate": rate }));
+    }
+
+    if state.remaining == 0 {
+        return Response::error(403, "API rate limit exceeded for installation ID 1.");
+    }
+    state.remaining -= 1;
+
+    let segments = request.path.trim_matches('/').split('/').collect::<Vec<_>>();
+    match (&request.method[..], &segments[..]) {
+        ("GET", &["repos", owner, name, "labels"]) => {
+            let full_name = format!("{}/{}", owner, name);
+            match state.repos.get(&full_name) {
+                Some(repo) => {
+                    let labels = repo.labels.iter().map(label_json).collect::<Vec<_>>();
+                    page(state, request, labels)
+                }
+                None => Response::not_found(),
+            }
+        }
+        ("GET", &["repos", owner, name, "labels", label]) => {
+            let full_name = format!("{}/{}", owner, name);
+            let label = percent_decode(label).to_lowercase();
+            match state.repos.get(&full_name) {
+                Some(repo) => match repo.labels.iter().find(|existing| existing.name.to_lowercase() == label) {
+                    Some(existing) => Response::json(200, label_json(existing)),
+                    None => Response::not_found(),
+                },
+                None => Response::not_found(),
+            }
+        }
+        ("POST", &["repos", owner, name, "labels"]) => {
+            let full_name = format!("{}/{}", owner, name);
+            let body: Value = match serde_json::from_slice(&request.body[..]) {
+                Ok(body) => body,
+                Err(_) => return Response::error(400, "Problems parsing JSON"),
+            };
+
+            let label = match (body["name"].as_str(), body["color"].as_str()) {
+                (Some(name), Some(color)) => Label {
+                    name: name.to_owned(),
+                    color: color.to_owned(),
+                    description: body["description"].as_str().map(str::to_owned),
+                },
+                _ => return Response::error(422, "Validation Failed"),
+            };
+
+            match state.repos.get_mut(&full_name) {
+                Some(repo) => {
+                    // GitHub compares label names case-insensitively.
+                    if repo.labels.iter().any(|existing| existing.name.to_lowercase() == label.name.to_lowercase()) {
+                        return Response::error(422, "Validation Failed");
+                    }
+
+                    let json = label_json(&label);
+                    repo.labels.push(label);
+                    Response::json(201, json)
+                }
+                None => Response::not_found(),
+            }
+        }
+        ("DELETE", &["repos", owner, name, "labels", label]) => {
+            let full_name = format!("{}/{}", owner, name);
+            let label = percent_decode(label).to_lowercase();
+            match state.repos.get_mut(&full_name) {
+                Some(repo) => match repo.labels.iter().position(|existing| existing.name.to_lowercase() == label) {
+                    Some(index) => {
+                        repo.labels.remove(index);
+                        Response::json(204, Value::Null)
+                    }
+                    None => Response::not_found(),
+                },
+                None => Response::not_found(),
+            }
+        }
+        ("GET", &["repos", owner, name, "issues"]) => {
+            let full_name = format!("{}/{}", owner, name);
+            match state.repos.get(&full_name) {
+                Some(repo) => {
+                    let issues = repo.issues
+                        .iter()
+                        .map(|issue| {
+                            json!({
+                                "number": issue.number,
+                                "title": issue.title,
+                                "state": "open",
+                                "labels": issue.labels.iter().map(|name| json!({ "name": name })).collect::<Vec<_>>(),
+                            })
+                        })
+                        .collect::<Vec<_>>();
+                    page(state, request, issues)
+                }
+                None => Response::not_found(),
+            }
+        }
+        ("POST", &["graphql"]) => graphql(state, request),
+        ("GET", &["orgs", org, "repos"]) => {
+            if !state.orgs.contains(org) {
+                return Response::not_found();
+            }
+
+            let repos = repos_where(state, |full_name, _| full_name.starts_with(&format!("{}/", org)));
+            page(state, request, repos)
+        }
+        ("GET", &["users", user, "repos"]) => {
+            let repos = repos_where(state, |full_name, _| full_name.starts_with(&format!("{}/", user)));
+            page(state, request, repos)
+        }
+        ("GET", &["orgs", org, "teams", team, "repos"]) => {
+            let members = match state.teams.get(&format!("{}/{}", org, team)) {
+                Some(members) => members.clone(),
+                None => return Response::not_found(),
+            };
+
+            let repos = repos_where(state, |full_name, _| members.iter().any(|member| member == full_name));
+            page(state, request, repos)
+        }
+        ("GET", &["search", "repositories"]) => {
+            // Queries look like "topic:backend+user:acme".
+            let query = request.query.get("q").cloned().unwrap_or_default();
+            let mut topic = None;
+            let mut user = None;
+            for term in query.split(['+', ' ']) {
+                if let Some(name) = term.strip_prefix("topic:") {
+                    topic = Some(name.to_owned());
+                } else if let Some(name) = term.strip_prefix("user:") {
+                    user = Some(format!("{}/", name));
+                }
+            }
+
+            let repos = repos_where(state, |full_name, repo| {
+                topic.as_ref().is_some_and(|topic| repo.topics.contains(topic))
+                    && user.as_ref().is_none_or(|user| full_name.starts_with(&user[..]))
+            });
+
+            // Search results are wrapped in an object, and paginated inside it.
+            let mut response = page(state, request, repos);
+            let count = response.body.as_array().map_or(0, |items| items.len());
+            response.body = json!({ "total_count": count, "incomplete_results": false, "items": response.body });
+            response
+        }
+        _ => Response::not_found(),
+    }
+}
+
+/// Answers a GraphQL query: either for the repositories of an owner, or for the labels of the repositories given by the
+/// variables `owner0`, `name0` and `after0`, `owner1`, `name1` and `after1`, and so on.
+fn graphql(state: &State, request: &Request) -> Response {
+    // GitHub still responds with 200 OK once the rate limit is exhausted.
+    if state.graphql_rate_limited {
+        let error = json!({ "type": "RATE_LIMITED", "message": "API rate limit exceeded for user ID 1." });
+        return Response::json(200, json!({ "data": Value::Null, "errors": [error] }));
+    }
+
+    let body: Value = match serde_json::from_slice(&request.body[..]) {
+        Ok(body) => body,
+        Err(_) => return Response::error(400, "Problems parsing JSON"),
+    };
+
+    let query = body["query"].as_str().unwrap_or_default();
+    let variables = &body["variables"];
+    let mut data = serde_json::Map::new();
+    let mut errors = Vec::new();
+
+    if query.contains("repositoryOwner(") {
+        let login = variables["login"].as_str().unwrap_or_default();
+        let prefix = format!("{}/", login);
+
+        if state.orgs.contains(login) || state.repos.keys().any(|full_name| full_name.starts_with(&prefix[..])) {
+            let repos = repos_where(state, |full_name, _| full_name.starts_with(&prefix[..]))
+                .into_iter()
+                .map(|repo| json!({ "name": repo["name"], "owner": repo["owner"], "isArchived": repo["archived"] }))
+                .collect();
+            let repositories = connection(state, repos, variables["after"].as_str());
+            data.insert("repositoryOwner".to_owned(), json!({ "repositories": repositories }));
+        } else {
+            data.insert("repositoryOwner".to_owned(), Value::Null);
+            errors.push(json!({
+                "type": "NOT_FOUND",
+                "path": ["repositoryOwner"],
+                "message": format!("Could not resolve to a RepositoryOwner with the login of '{}'.", login),
+            }));
+        }
+    } else {
+        let count_issues = query.contains("issues {");
+
+        let mut index = 0;
+        while let (Some(owner), Some(name)) = (
+            variables[format!("owner{}", index)].as_str(),
+            variables[format!("name{}", index)].as_str(),
+        ) {
+            let alias = format!("r{}", index);
+            let full_name = format!("{}/{}", owner, name);
+
+            match state.repos.get(&full_name) {
+                Some(repo) => {
+                    let labels = repo.labels
+                        .iter()
+                        .map(|label| {
+                            let mut json = json!({
+                                "name": label.name,
+                                "color": label.color,
+                                "description": label.description,
+                            });
+
+                            if count_issues {
+                                let issues = repo.issues.iter().filter(|issue| issue.labels.contains(&label.name));
+                                json["issues"] = json!({ "totalCount": issues.count() });
+                                json["pullRequests"] = json!({ "totalCount": 0 });
+                            }
+
+                            json
+                        })
+                        .collect();
+
+                    let after = variables[format!("after{}", index)].as_str();
+                    data.insert(alias, json!({ "labels": connection(state, labels, after) }));
+                }
+                None => {
+                    data.insert(alias.clone(), Value::Null);
+                    errors.push(json!({
+                        "type": "NOT_FOUND",
+                        "path": [alias],
+                        "message": format!("Could not resolve to a Repository with the name '{}'.", full_name),
+                    }));
+                }
+            }
+
+            index += 1;
+        }
+    }
+
+    let mut body = json!({ "data": data });
+    if !errors.is_empty() {
+        body["errors"] = Value::Array(errors);
+    }
+
+    Response::json(200, body)
+}
+
+/// Returns the page of `items` after the cursor `after` as a GraphQL connection, with `page_size` items in each page.
+fn connection(state: &State, items: Vec<Value>, after: Option<&str>) -> Value {
+    let start = after
+        .and_then(|after| after.strip_prefix("cursor:"))
+        .and_then(|offset| offset.parse().ok())
+        .unwrap_or(0);
+    let end = (start + state.page_size).min(items.len());
+
+    json!({
+        "pageInfo": { "hasNextPage": end < items.len(), "endCursor": format!("cursor:{}", end) },
+        "nodes": items[start.min(end)..end].to_vec(),
+    })
+}
+
+/// Returns the JSON for each repository for which `filter` returns `true`.
+fn repos_where<F: Fn(&str, &Repo) -> bool>(state: &State, filter: F) -> Vec<Value> {
+    state
+        .repos
+        .iter()
+        .filter(|&(full_name, repo)| filter(&full_name[..], repo))
+        .map(|(full_name, repo)| {
+            let mut parts = full_name.splitn(2, '/');
+            let owner = parts.next().unwrap_or_default();
+            let name = parts.next().unwrap_or_default();
+
+            json!({
+                "name": name,
+                "full_name": full_name,
+                "owner": { "login": owner },
+                "archived": repo.archived,
+                "topics": repo.topics,
+            })
+        })
+        .collect()
+}
+
+/// Decodes the percent-encoded characters in a segment of a path, e.g. "good%20first%20issue".
+fn percent_decode(segment: &str) -> String {
+    let bytes = segment.as_bytes();
+    let mut decoded = Vec::new();
+    let mut index = 0;
+
+    while index < bytes.len() {
+        let escaped = if bytes[index] == b'%' && index + 2 < bytes.len() {
+            str::from_utf8(&bytes[index + 1..index + 3])
+                .ok()
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        } else {
+            None
+        };
+
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                index += 3;
+            }
+            None => {
+                decoded.push(bytes[index]);
+                index += 1;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&decoded[..]).into_owned()
+}
+
+fn label_json(label: &Label) -> Value {
+    json!({
+        "url": format!("https://api.github.com/labels/{}", label.name),
+        "name": label.name,
+        "color": label.color,
+        "description": label.description,
+        "default": false,
+    })
+}
+
+/// Returns the page of `items` asked for by `request`, with a `Link` header pointing to the next and last pages if
+/// there are more.
+fn page(state: &State, request: &Request, items: Vec<Value>) -> Response {
+    let per_page = request
+        .query
+        .get("per_page")
+        .and_then(|per_page| per_page.parse().ok())
+        .unwrap_or(state.page_size)
+        .clamp(1, 100);
+    let number = request.query.get("page").and_then(|page| page.parse().ok()).unwrap_or(1).max(1);
+    let last = items.len().div_ceil(per_page).max(1);
+
+    let page = items
+        .into_iter()
+        .skip((number - 1) * per_page)
+        .take(per_page)
+        .collect::<Vec<_>>();
+
+    let link = if number < last {
+        let url = |page: usize| {
+            let mut query = request.query.clone();
+            query.insert("page".to_owned(), page.to_string());
+            query.insert("per_page".to_owned(), per_page.to_string());
+
+            let query = query
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect::<Vec<_>>()
+                .join("&");
+            format!("{}?{}", request.path, query)
+        };
+
+        Some(format!(
+            "<{}{}>; rel=\"next\", <{}{}>; rel=\"last\"",
+            request.headers.get("host").map(|host| format!("http://{}", host)).unwrap_or_default(),
+            url(number + 1),
+            request.headers.get("host").map(|host| format!("http://{}", host)).unwrap_or_default(),
+            url(last)
+        ))
+    } else {
+        None
+    };
+
+    Response {
+        status: 200,
+        body: Value::Array(page),
+        link,
+        etag: None,
+    }
+}
+
+fn reason(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        201 => "Created",
+        204 => "No Content",
+        304 => "Not Modified",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        _ => "Unknown",
+    }
+}